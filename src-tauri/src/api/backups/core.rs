@@ -0,0 +1,192 @@
+use crate::api::history::core::{record_change, ChangeSource, Operation};
+use crate::api::servers::core::{rebuild_server_entry, SavedEntries};
+use crate::clients::{
+    get_client, lock_config_file, lock_mutations, read_servers, update_servers, write_config_file,
+    ClientServerConfig, ClientTarget, McpClient,
+};
+use crate::launcher::Launcher;
+use crate::utils::fs::path_key;
+use anyhow::{anyhow, bail, Context, Result};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::Manager;
+
+/// Directory inside the MCPHub data dir that config backups are kept in.
+const BACKUPS_DIR: &str = "backups";
+
+/// How many backups are kept per config file; older ones are pruned.
+const MAX_BACKUPS: usize = 10;
+
+const BACKUP_EXTENSION: &str = "bak";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConfigBackup {
+    pub id: String,
+    /// Milliseconds since the Unix epoch.
+    #[serde(rename = "createdAt")]
+    pub created_at: u64,
+    pub size: u64,
+}
+
+/// Backups of one config file live in their own directory, named after the file's full
+/// path so configs of different clients and workspaces never mix.
+fn backups_dir_for(app_handle: &tauri::AppHandle, config_path: &Path) -> Result<PathBuf> {
+    Ok(app_handle
+        .path()
+        .app_data_dir()?
+        .join(BACKUPS_DIR)
+        .join(path_key(config_path)))
+}
+
+/// Copies the current contents of `config_path` into its backup directory, skipping the
+/// copy when it matches the newest backup. Returns `None` when there is no file yet.
+pub fn create_backup(
+    app_handle: &tauri::AppHandle,
+    config_path: &Path,
+) -> Result<Option<ConfigBackup>> {
+    let contents = match std::fs::read(config_path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            return Err(e)
+                .with_context(|| format!("Failed to read {}", config_path.to_string_lossy()))
+        }
+    };
+    let backups_dir = backups_dir_for(app_handle, config_path)?;
+    let backups = list_backups_in(&backups_dir)?;
+    if let Some(latest) = backups.first() {
+        if std::fs::read(backup_file(&backups_dir, &latest.id)).ok() == Some(contents.clone()) {
+            return Ok(Some(latest.clone()));
+        }
+    }
+
+    std::fs::create_dir_all(&backups_dir)?;
+    let mut created_at = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64;
+    // Two writes within the same millisecond still get distinct backups.
+    if let Some(latest) = backups.first() {
+        created_at = created_at.max(latest.created_at + 1);
+    }
+    let id = created_at.to_string();
+    std::fs::write(backup_file(&backups_dir, &id), &contents)?;
+    debug!("Backed up {} as {}", config_path.to_string_lossy(), id);
+
+    for stale in backups.iter().skip(MAX_BACKUPS - 1) {
+        let _ = std::fs::remove_file(backup_file(&backups_dir, &stale.id));
+    }
+
+    Ok(Some(ConfigBackup {
+        id,
+        created_at,
+        size: contents.len() as u64,
+    }))
+}
+
+/// Backups of `config_path`, newest first.
+pub fn list_backups(
+    app_handle: &tauri::AppHandle,
+    config_path: &Path,
+) -> Result<Vec<ConfigBackup>> {
+    list_backups_in(&backups_dir_for(app_handle, config_path)?)
+}
+
+/// Puts the backup `backup_id` back in place of `config_path`. The file being replaced is
+/// backed up first, so a restore can itself be undone.
+pub fn restore_backup(
+    app_handle: &tauri::AppHandle,
+    config_path: &Path,
+    backup_id: &str,
+) -> Result<()> {
+    if backup_id.is_empty() || !backup_id.chars().all(|c| c.is_ascii_digit()) {
+        bail!("Invalid backup id `{}`", backup_id);
+    }
+    let backups_dir = backups_dir_for(app_handle, config_path)?;
+    let contents = std::fs::read_to_string(backup_file(&backups_dir, backup_id))
+        .map_err(|_| anyhow!("Backup {} not found", backup_id))?;
+    let _lock = lock_config_file(app_handle, config_path)?;
+    write_config_file(app_handle, config_path, &contents)
+}
+
+pub async fn list_client_config_backups_function(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+) -> Result<Vec<ConfigBackup>> {
+    let config_path = get_client(app_handle, target)?.config_path()?;
+    list_backups(app_handle, &config_path)
+}
+
+pub async fn restore_client_config_backup_function(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+    backup_id: &str,
+) -> Result<()> {
+    let _guard = lock_mutations(app_handle);
+    let client = get_client(app_handle, target)?;
+    let config_path = client.config_path()?;
+    // The journal is best effort here: a backup may hold a file that doesn't parse.
+    let before = read_servers(client.as_ref()).unwrap_or_default();
+    restore_backup(app_handle, &config_path, backup_id)?;
+    let after = match read_servers(client.as_ref()) {
+        Ok(_) => rebuild_launchers(app_handle, target, client.as_ref())?,
+        Err(_) => HashMap::new(),
+    };
+    let source = ChangeSource {
+        operation: Operation::Restore,
+        server_id: None,
+        trigger: "restore_client_config_backup",
+    };
+    record_change(app_handle, target, &source, None, before, after)
+}
+
+/// Rebuilds the launchers the restored entries run, which may have been removed or
+/// changed since the backup was taken. Returns the entries as they end up.
+fn rebuild_launchers(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+    client: &dyn McpClient,
+) -> Result<HashMap<String, ClientServerConfig>> {
+    let mut saved = SavedEntries::default();
+    let written = update_servers(app_handle, client, |servers| {
+        for (server_id, server_config) in servers.iter_mut() {
+            if Launcher::runs_launcher(server_config) {
+                *server_config =
+                    rebuild_server_entry(app_handle, target, server_id, server_config, &mut saved)?;
+            }
+        }
+        Ok(())
+    });
+    Ok(saved.finish(written)?.after)
+}
+
+fn backup_file(backups_dir: &Path, id: &str) -> PathBuf {
+    backups_dir.join(format!("{}.{}", id, BACKUP_EXTENSION))
+}
+
+fn list_backups_in(backups_dir: &Path) -> Result<Vec<ConfigBackup>> {
+    let entries = match std::fs::read_dir(backups_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e.into()),
+    };
+    let mut backups: Vec<ConfigBackup> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension()? != BACKUP_EXTENSION {
+                return None;
+            }
+            let id = path.file_stem()?.to_str()?.to_string();
+            let created_at = id.parse().ok()?;
+            let size = entry.metadata().ok()?.len();
+            Some(ConfigBackup {
+                id,
+                created_at,
+                size,
+            })
+        })
+        .collect();
+    backups.sort_by_key(|backup| std::cmp::Reverse(backup.created_at));
+    Ok(backups)
+}
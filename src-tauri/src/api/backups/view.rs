@@ -0,0 +1,30 @@
+use super::core::{
+    list_client_config_backups_function, restore_client_config_backup_function, ConfigBackup,
+};
+use crate::clients::{ClientTarget, ConfigScope};
+use crate::error::AppError;
+
+#[tauri::command]
+pub async fn list_client_config_backups(
+    app_handle: tauri::AppHandle,
+    client_id: Option<String>,
+    scope: Option<ConfigScope>,
+) -> Result<Vec<ConfigBackup>, AppError> {
+    let target = ClientTarget::new(client_id, scope);
+    list_client_config_backups_function(&app_handle, &target)
+        .await
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn restore_client_config_backup(
+    app_handle: tauri::AppHandle,
+    client_id: Option<String>,
+    scope: Option<ConfigScope>,
+    backup_id: &str,
+) -> Result<(), AppError> {
+    let target = ClientTarget::new(client_id, scope);
+    restore_client_config_backup_function(&app_handle, &target, backup_id)
+        .await
+        .map_err(AppError::from)
+}
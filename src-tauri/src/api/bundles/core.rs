@@ -0,0 +1,351 @@
+use crate::api::history::core::{apply_change, ChangeSource, Operation};
+use crate::api::servers::core::{
+    build_server_entry, catalog_server, input_arg_values, instance_catalog_id, instance_id,
+    is_catalog_server, refuse_unmanaged, secret_env_check, unpark_server, SavedEntries,
+};
+use crate::clients::{
+    get_client, lock_mutations, read_servers, ClientServerConfig, ClientTarget, MCPHUB_CREATOR,
+};
+use crate::error::AppError;
+use crate::launcher::Launcher;
+use crate::utils::fs::write_atomic;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Format version written into new bundles. Bundles from newer versions are refused.
+pub const BUNDLE_VERSION: u32 = 2;
+
+/// A portable set of catalog servers with their settings, without any secret values.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ServerBundle {
+    version: u32,
+    /// Milliseconds since the Unix epoch.
+    #[serde(rename = "exportedAt")]
+    exported_at: u64,
+    servers: Vec<BundleServer>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BundleServer {
+    #[serde(rename = "catalogId")]
+    catalog_id: String,
+    /// Name of the instance; the default instance has none.
+    #[serde(
+        rename = "instanceName",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    instance_name: Option<String>,
+    #[serde(default)]
+    env: BTreeMap<String, BundleEnvVar>,
+    /// Values of the server's input arguments by name.
+    #[serde(rename = "inputArgs", default)]
+    input_args: BTreeMap<String, Vec<String>>,
+    /// Values of the single input argument servers had in version 1 bundles.
+    #[serde(rename = "inputArg", default, skip_serializing)]
+    legacy_input_arg: Vec<String>,
+}
+
+impl BundleServer {
+    fn instance_id(&self) -> Result<String> {
+        instance_id(&self.catalog_id, self.instance_name.as_deref())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BundleEnvVar {
+    /// Missing for secrets, which are asked for on import instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    value: Option<String>,
+    #[serde(default)]
+    secret: bool,
+}
+
+/// An env value the bundle leaves out and the importer has to provide.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BundlePrompt {
+    #[serde(rename = "instanceId")]
+    instance_id: String,
+    key: String,
+    secret: bool,
+}
+
+/// What importing a bundle would install, and the values to ask for first.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundlePreview {
+    bundle: ServerBundle,
+    prompts: Vec<BundlePrompt>,
+}
+
+/// The servers an import installed.
+#[derive(Debug, Serialize)]
+pub struct BundleImport {
+    /// Instance ids of every server in the bundle.
+    imported: Vec<String>,
+    /// Those of them that replaced an MCPHub server already in the config.
+    replaced: Vec<String>,
+}
+
+/// Writes the MCPHub servers `server_ids` of `target` to a bundle file at `path`.
+/// Values of env variables that look like credentials are left out.
+pub async fn export_server_bundle_function(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+    server_ids: Vec<String>,
+    path: &Path,
+) -> Result<ServerBundle> {
+    let configured = read_servers(get_client(app_handle, target)?.as_ref())?;
+    let mut servers = vec![];
+    for server_id in &server_ids {
+        let server_config =
+            configured
+                .get(server_id)
+                .ok_or_else(|| AppError::ServerNotInstalled {
+                    server_id: server_id.clone(),
+                })?;
+        if server_config.command_creator != MCPHUB_CREATOR {
+            bail!(
+                "{} is not managed by MCPHub; adopt it before exporting",
+                server_id
+            );
+        }
+        let catalog_id = instance_catalog_id(server_id, server_config).to_string();
+        let instance_name = (*server_id != catalog_id).then(|| {
+            server_id
+                .strip_prefix(&format!("{}-", catalog_id))
+                .unwrap_or(server_id)
+                .to_string()
+        });
+        let is_secret = secret_env_check(app_handle, server_id, Some(server_config));
+        let mut env: BTreeMap<String, BundleEnvVar> = server_config
+            .env
+            .iter()
+            .map(|(key, value)| {
+                let secret = is_secret(key);
+                let value = (!secret).then(|| value.clone());
+                (key.clone(), BundleEnvVar { value, secret })
+            })
+            .collect();
+        if let Some(launcher) = Launcher::from_entry(server_config) {
+            for key in launcher.spec.secrets.into_keys() {
+                let var = BundleEnvVar {
+                    value: None,
+                    secret: true,
+                };
+                env.insert(key, var);
+            }
+        }
+        servers.push(BundleServer {
+            catalog_id,
+            instance_name,
+            env,
+            input_args: input_arg_values(&server_config.input_args),
+            legacy_input_arg: vec![],
+        });
+    }
+
+    let bundle = ServerBundle {
+        version: BUNDLE_VERSION,
+        exported_at: SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64,
+        servers,
+    };
+    write_atomic(path, serde_json::to_string_pretty(&bundle)?.as_bytes())?;
+    Ok(bundle)
+}
+
+/// Reads the bundle at `path` and checks it against the current catalog.
+fn load_bundle(app_handle: &tauri::AppHandle, path: &Path) -> Result<ServerBundle> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.to_string_lossy()))?;
+    let bundle: ServerBundle = serde_json::from_str(&text)
+        .with_context(|| format!("{} is not a server bundle", path.to_string_lossy()))?;
+    if bundle.version > BUNDLE_VERSION {
+        bail!(
+            "Bundle version {} is newer than this MCPHub supports ({})",
+            bundle.version,
+            BUNDLE_VERSION
+        );
+    }
+    let mut instance_ids = HashSet::new();
+    for server in &bundle.servers {
+        if !is_catalog_server(app_handle, &server.catalog_id)? {
+            return Err(AppError::ServerNotFound {
+                server_id: server.catalog_id.clone(),
+            }
+            .into());
+        }
+        let instance_id = server.instance_id()?;
+        if !instance_ids.insert(instance_id.clone()) {
+            bail!("{} appears more than once in the bundle", instance_id);
+        }
+    }
+    Ok(bundle)
+}
+
+pub async fn inspect_server_bundle_function(
+    app_handle: &tauri::AppHandle,
+    path: &Path,
+) -> Result<BundlePreview> {
+    let bundle = load_bundle(app_handle, path)?;
+    let mut prompts = vec![];
+    for server in &bundle.servers {
+        let instance_id = server.instance_id()?;
+        for (key, var) in &server.env {
+            if var.value.is_none() {
+                prompts.push(BundlePrompt {
+                    instance_id: instance_id.clone(),
+                    key: key.clone(),
+                    secret: var.secret,
+                });
+            }
+        }
+    }
+    Ok(BundlePreview { bundle, prompts })
+}
+
+/// Installs every server of the bundle at `path` into `target` with a single write.
+/// `values` holds the env values the bundle left out, by instance id and key; nothing
+/// is written unless all of them are given. Servers configured outside MCPHub under
+/// the same ids are never replaced.
+pub async fn import_server_bundle_function(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+    path: &Path,
+    values: HashMap<String, HashMap<String, String>>,
+) -> Result<BundleImport> {
+    let _guard = lock_mutations(app_handle);
+    let bundle = load_bundle(app_handle, path)?;
+    let mut entries = vec![];
+    for server in bundle.servers {
+        let instance_id = server.instance_id()?;
+        let given = values.get(&instance_id);
+        let mut env = HashMap::new();
+        for (key, var) in server.env {
+            let value = match var.value {
+                Some(value) => value,
+                None => given
+                    .and_then(|given| given.get(&key))
+                    .cloned()
+                    .with_context(|| format!("Missing value for {} of {}", key, instance_id))?,
+            };
+            env.insert(key, value);
+        }
+        let mut input_args = server.input_args;
+        if !server.legacy_input_arg.is_empty() {
+            let catalog_server = catalog_server(app_handle, &server.catalog_id)?;
+            input_args.extend(catalog_server.legacy_input_args(server.legacy_input_arg));
+        }
+        let input_args = (!input_args.is_empty()).then_some(input_args);
+        let entry = build_server_entry(
+            app_handle,
+            target,
+            &server.catalog_id,
+            &instance_id,
+            Some(env),
+            input_args,
+        )?;
+        entries.push((instance_id, entry));
+    }
+
+    let instance_ids: Vec<String> = entries.iter().map(|(id, _)| id.clone()).collect();
+    let source = ChangeSource {
+        operation: Operation::Import,
+        server_id: None,
+        trigger: "import_server_bundle",
+    };
+    // Either every entry is written with its launcher and secrets, or none is.
+    let mut saved = SavedEntries::default();
+    let mut replaced = vec![];
+    let written = entries
+        .iter()
+        .try_for_each(|(_, entry)| entry.save(&mut saved))
+        .and_then(|()| {
+            apply_change(app_handle, target, source, |servers| {
+                let imported = entries
+                    .into_iter()
+                    .map(|(instance_id, entry)| (instance_id, entry.config));
+                replaced = add_imported(servers, imported)?;
+                Ok(())
+            })
+        });
+    saved.finish(written)?;
+    for instance_id in &instance_ids {
+        unpark_server(app_handle, target, instance_id)?;
+    }
+    Ok(BundleImport {
+        imported: instance_ids,
+        replaced,
+    })
+}
+
+/// Adds imported entries to `servers`, unless one would replace a server configured
+/// outside MCPHub. Returns the ids of the MCPHub servers they replaced.
+fn add_imported(
+    servers: &mut HashMap<String, ClientServerConfig>,
+    imported: impl IntoIterator<Item = (String, ClientServerConfig)>,
+) -> Result<Vec<String>> {
+    let imported: Vec<(String, ClientServerConfig)> = imported.into_iter().collect();
+    refuse_unmanaged(servers, imported.iter().map(|(instance_id, _)| instance_id))?;
+    let mut replaced = vec![];
+    for (instance_id, server_config) in imported {
+        if servers.insert(instance_id.clone(), server_config).is_some() {
+            replaced.push(instance_id);
+        }
+    }
+    Ok(replaced)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn servers(value: serde_json::Value) -> HashMap<String, ClientServerConfig> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn imported(value: serde_json::Value) -> Vec<(String, ClientServerConfig)> {
+        servers(value).into_iter().collect()
+    }
+
+    #[test]
+    fn imports_report_the_servers_they_replace() {
+        let mut current = servers(json!({
+            "github": { "command": "npx", "args": ["old"], "commandCreator": "MCPHub" },
+            "mine": { "command": "python" },
+        }));
+        let replaced = add_imported(
+            &mut current,
+            imported(json!({
+                "github": { "command": "npx", "args": ["new"], "commandCreator": "MCPHub" },
+                "slack": { "command": "npx", "commandCreator": "MCPHub" },
+            })),
+        )
+        .unwrap();
+        assert_eq!(replaced, vec!["github"]);
+        assert_eq!(current["github"].args, vec!["new"]);
+        assert!(current.contains_key("slack"));
+        assert_eq!(current["mine"].command, "python");
+    }
+
+    #[test]
+    fn imports_refuse_to_replace_servers_configured_by_hand() {
+        let mut current = servers(json!({ "github": { "command": "docker" } }));
+        let error = add_imported(
+            &mut current,
+            imported(json!({
+                "slack": { "command": "npx", "commandCreator": "MCPHub" },
+                "github": { "command": "npx", "commandCreator": "MCPHub" },
+            })),
+        )
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("github is configured outside MCPHub"));
+        assert_eq!(current.len(), 1);
+        assert_eq!(current["github"].command, "docker");
+    }
+}
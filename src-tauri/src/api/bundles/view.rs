@@ -0,0 +1,46 @@
+use super::core::{
+    export_server_bundle_function, import_server_bundle_function, inspect_server_bundle_function,
+    BundleImport, BundlePreview, ServerBundle,
+};
+use crate::clients::{ClientTarget, ConfigScope};
+use crate::error::AppError;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+#[tauri::command]
+pub async fn export_server_bundle(
+    app_handle: tauri::AppHandle,
+    client_id: Option<String>,
+    scope: Option<ConfigScope>,
+    server_ids: Vec<String>,
+    path: PathBuf,
+) -> Result<ServerBundle, AppError> {
+    let target = ClientTarget::new(client_id, scope);
+    export_server_bundle_function(&app_handle, &target, server_ids, &path)
+        .await
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn inspect_server_bundle(
+    app_handle: tauri::AppHandle,
+    path: PathBuf,
+) -> Result<BundlePreview, AppError> {
+    inspect_server_bundle_function(&app_handle, &path)
+        .await
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn import_server_bundle(
+    app_handle: tauri::AppHandle,
+    client_id: Option<String>,
+    scope: Option<ConfigScope>,
+    path: PathBuf,
+    values: Option<HashMap<String, HashMap<String, String>>>,
+) -> Result<BundleImport, AppError> {
+    let target = ClientTarget::new(client_id, scope);
+    import_server_bundle_function(&app_handle, &target, &path, values.unwrap_or_default())
+        .await
+        .map_err(AppError::from)
+}
@@ -0,0 +1,57 @@
+use crate::clients::{all_clients, needs_restart};
+use crate::error::AppError;
+use crate::utils::process::{find_processes, restart_processes};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FrontendClient {
+    id: String,
+    name: String,
+    #[serde(rename = "isInstalled")]
+    is_installed: bool,
+    #[serde(rename = "configPath")]
+    config_path: String,
+    scopes: Vec<String>,
+    /// The client app is running and has to be restarted to see config changes.
+    #[serde(rename = "needsRestart")]
+    needs_restart: bool,
+}
+
+pub async fn load_all_frontend_clients(app_handle: &tauri::AppHandle) -> Vec<FrontendClient> {
+    all_clients(app_handle)
+        .iter()
+        .map(|client| FrontendClient {
+            id: client.id().to_string(),
+            name: client.name().to_string(),
+            is_installed: client.is_installed(),
+            config_path: client
+                .config_path()
+                .map(|path| path.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            scopes: client.scopes().iter().map(|s| s.to_string()).collect(),
+            needs_restart: needs_restart(client.as_ref()),
+        })
+        .collect()
+}
+
+/// Quits the client app and starts it again so it reloads its config. Returns false
+/// when it wasn't running.
+pub async fn restart_client_function(
+    app_handle: &tauri::AppHandle,
+    client_id: &str,
+) -> Result<bool> {
+    let client = all_clients(app_handle)
+        .into_iter()
+        .find(|client| client.id() == client_id)
+        .ok_or_else(|| AppError::ClientNotFound {
+            client_id: client_id.to_string(),
+        })?;
+    let processes = find_processes(client.process_names());
+    if processes.is_empty() {
+        return Ok(false);
+    }
+    // Waiting for the apps to quit must not hold up one of the async runtime's workers.
+    tauri::async_runtime::spawn_blocking(move || restart_processes(&processes)).await??;
+    Ok(true)
+}
@@ -0,0 +1,17 @@
+use super::core::{load_all_frontend_clients, restart_client_function, FrontendClient};
+use crate::error::AppError;
+
+#[tauri::command]
+pub async fn get_clients(app_handle: tauri::AppHandle) -> Vec<FrontendClient> {
+    load_all_frontend_clients(&app_handle).await
+}
+
+#[tauri::command]
+pub async fn restart_client(
+    app_handle: tauri::AppHandle,
+    client_id: &str,
+) -> Result<bool, AppError> {
+    restart_client_function(&app_handle, client_id)
+        .await
+        .map_err(AppError::from)
+}
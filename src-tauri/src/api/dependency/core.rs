@@ -8,10 +8,14 @@ use tauri_plugin_store::StoreExt;
 use xshell::{cmd, Shell};
 #[cfg(target_os = "windows")]
 use zip::ZipArchive;
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", target_os = "linux"))]
 use {flate2::read::GzDecoder, tar::Archive};
 
-use crate::utils::os::{detect_shell, get_home};
+use crate::utils::os::detect_shell;
+#[cfg(target_os = "linux")]
+use crate::utils::os::get_data_dir;
+#[cfg(not(target_os = "linux"))]
+use crate::utils::os::get_home;
 
 pub struct NpmHandler;
 pub struct UVHandler;
@@ -40,7 +44,7 @@ impl NpmHandler {
         }
         trace!("Running check node command");
 
-        #[cfg(target_os = "macos")]
+        #[cfg(any(target_os = "macos", target_os = "linux"))]
         let cmd_output = cmd!(shell, "{shell_name} -ic 'which node'")
             .quiet()
             .read()?;
@@ -58,6 +62,7 @@ impl NpmHandler {
     pub async fn install(app_handle: &tauri::AppHandle) -> Result<()> {
         trace!("Installing Node.js");
         let store = app_handle.store(APP_STATE_FILENAME)?;
+        #[cfg(not(target_os = "linux"))]
         let home_dir_str = get_home()?.to_string_lossy().to_string();
         let node_version = "v22.11.0";
 
@@ -88,6 +93,17 @@ impl NpmHandler {
                     "win-arm64.zip"
                 }
             }
+            #[cfg(target_os = "linux")]
+            {
+                #[cfg(target_arch = "aarch64")]
+                {
+                    "linux-arm64.tar.gz"
+                }
+                #[cfg(target_arch = "x86_64")]
+                {
+                    "linux-x64.tar.gz"
+                }
+            }
         };
 
         let node_download_url = format!(
@@ -100,6 +116,8 @@ impl NpmHandler {
         let node_dir = format!("{}/.node", home_dir_str);
         #[cfg(target_os = "windows")]
         let node_dir = format!("{}\\AppData\\Local\\node", home_dir_str);
+        #[cfg(target_os = "linux")]
+        let node_dir = get_data_dir()?.join("node").to_string_lossy().to_string();
 
         trace!("Creating node directory at {}", node_dir);
         fs::create_dir_all(&node_dir)?;
@@ -109,7 +127,7 @@ impl NpmHandler {
         let bytes = response.bytes().await?;
 
         trace!("Extracting archive");
-        #[cfg(target_os = "macos")]
+        #[cfg(any(target_os = "macos", target_os = "linux"))]
         {
             let gz = GzDecoder::new(Cursor::new(bytes));
             let mut archive = Archive::new(gz);
@@ -123,7 +141,17 @@ impl NpmHandler {
             archive.extract(&node_dir)?;
         }
 
-        store.set("node_path", node_dir);
+        // The archive holds a single `node-<version>-<arch>` directory, with the
+        // executables in its `bin` except on Windows.
+        let node_home = format!(
+            "{}/node-{}-{}",
+            node_dir,
+            node_version,
+            node_arch.split(".").next().unwrap()
+        );
+        #[cfg(any(target_os = "macos", target_os = "linux"))]
+        let node_home = format!("{}/bin", node_home);
+        store.set("node_path", node_home);
         store.set("use_system_node", false);
         trace!("All done");
         Ok(())
@@ -153,7 +181,7 @@ impl UVHandler {
 
         trace!("Running check node command");
 
-        #[cfg(target_os = "macos")]
+        #[cfg(any(target_os = "macos", target_os = "linux"))]
         let cmd_output = cmd!(shell, "{shell_name} -ic 'which uv'").read()?;
 
         #[cfg(target_os = "windows")]
@@ -169,6 +197,7 @@ impl UVHandler {
     pub async fn install(app_handle: &tauri::AppHandle) -> Result<()> {
         trace!("Installing UV");
         let store = app_handle.store(APP_STATE_FILENAME)?;
+        #[cfg(not(target_os = "linux"))]
         let home_dir_str = get_home()?.to_string_lossy().to_string();
         let uv_version = "0.5.5";
         let uv_arch = {
@@ -194,6 +223,17 @@ impl UVHandler {
                     "i686-pc-windows-msvc.zip"
                 }
             }
+            #[cfg(target_os = "linux")]
+            {
+                #[cfg(target_arch = "aarch64")]
+                {
+                    "aarch64-unknown-linux-gnu.tar.gz"
+                }
+                #[cfg(target_arch = "x86_64")]
+                {
+                    "x86_64-unknown-linux-gnu.tar.gz"
+                }
+            }
         };
 
         let uv_download_url = format!(
@@ -207,6 +247,8 @@ impl UVHandler {
         let uv_dir = format!("{}/.uv/bin", home_dir_str);
         #[cfg(target_os = "windows")]
         let uv_dir = format!("{}\\AppData\\Local\\uv\\bin", home_dir_str);
+        #[cfg(target_os = "linux")]
+        let uv_dir = get_data_dir()?.join("uv").join("bin").to_string_lossy().to_string();
 
         trace!("Creating uv directory at {}", uv_dir);
         fs::create_dir_all(&uv_dir)?;
@@ -216,7 +258,7 @@ impl UVHandler {
         let bytes = response.bytes().await?;
 
         trace!("Extracting archive");
-        #[cfg(target_os = "macos")]
+        #[cfg(any(target_os = "macos", target_os = "linux"))]
         {
             let gz = GzDecoder::new(Cursor::new(bytes));
             let mut archive = Archive::new(gz);
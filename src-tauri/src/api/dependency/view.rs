@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use tauri_plugin_store::StoreExt;
 
 use super::core::{NpmHandler, ResourceHandler, UVHandler};
+use crate::error::AppError;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DependencyStatus {
@@ -12,31 +13,32 @@ pub struct DependencyStatus {
 }
 
 #[tauri::command]
-pub async fn check_dependency(app_handle: tauri::AppHandle) -> DependencyStatus {
-    let status = DependencyStatus {
-        uv: UVHandler::detect(&app_handle).await.unwrap_or(false),
-        node: NpmHandler::detect(&app_handle).await.unwrap_or(false),
-        servers: ResourceHandler::detect(&app_handle).await.unwrap_or(false),
-    };
-    status
+pub async fn check_dependency(app_handle: tauri::AppHandle) -> Result<DependencyStatus, AppError> {
+    Ok(DependencyStatus {
+        uv: UVHandler::detect(&app_handle).await?,
+        node: NpmHandler::detect(&app_handle).await?,
+        servers: ResourceHandler::detect(&app_handle).await?,
+    })
 }
 
 #[tauri::command]
-pub async fn install_npm(app_handle: tauri::AppHandle) -> Result<(), String> {
+pub async fn install_npm(app_handle: tauri::AppHandle) -> Result<(), AppError> {
     NpmHandler::install(&app_handle)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(AppError::from)
 }
 
 #[tauri::command]
-pub async fn install_uv(app_handle: tauri::AppHandle) -> Result<(), String> {
+pub async fn install_uv(app_handle: tauri::AppHandle) -> Result<(), AppError> {
     UVHandler::install(&app_handle)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(AppError::from)
 }
 
 #[tauri::command]
-pub async fn check_resource(app_handle: tauri::AppHandle) -> bool {
+pub async fn check_resource(app_handle: tauri::AppHandle) -> Result<bool, AppError> {
     debug!("Start check_resource in backend");
-    ResourceHandler::detect(&app_handle).await.unwrap_or(false)
+    ResourceHandler::detect(&app_handle)
+        .await
+        .map_err(AppError::from)
 }
@@ -0,0 +1,498 @@
+use crate::api::servers::core::{
+    lost_secrets, mask_changes, rebuild_server_entry, secret_env_check, SavedEntries,
+};
+use crate::clients::{
+    get_client, lock_mutations, update_servers, ClientServerConfig, ClientTarget, ConfigScope,
+};
+use crate::launcher::Launcher;
+use crate::utils::secrets::mask_env;
+use anyhow::{anyhow, bail, Context, Result};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri_plugin_store::StoreExt;
+
+pub const HISTORY_FILENAME: &str = "History.json";
+
+const ENTRIES_KEY: &str = "entries";
+
+/// Oldest entries are dropped once the journal grows past this.
+const MAX_HISTORY_ENTRIES: usize = 200;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Operation {
+    Install,
+    Update,
+    Uninstall,
+    Revert,
+    Restore,
+    Adopt,
+    Disable,
+    Enable,
+    #[serde(rename = "activateProfile")]
+    ActivateProfile,
+    Import,
+}
+
+/// What is about to change a config, recorded alongside the change itself.
+pub struct ChangeSource<'a> {
+    pub operation: Operation,
+    /// The server the operation is about, if it is about a single one.
+    pub server_id: Option<&'a str>,
+    /// The command that asked for the change.
+    pub trigger: &'a str,
+}
+
+/// One mutation MCPHub made to a client config, with the server entries before and
+/// after it. The `after` map of an entry is the config version the entry id names.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HistoryEntry {
+    pub id: String,
+    /// Milliseconds since the Unix epoch.
+    pub timestamp: u64,
+    #[serde(rename = "clientId")]
+    pub client_id: String,
+    pub scope: ConfigScope,
+    #[serde(rename = "configPath")]
+    pub config_path: String,
+    pub operation: Operation,
+    #[serde(rename = "serverId")]
+    pub server_id: Option<String>,
+    pub trigger: String,
+    /// Id of the entry this one reverted.
+    #[serde(rename = "revertOf", default, skip_serializing_if = "Option::is_none")]
+    pub revert_of: Option<String>,
+    pub before: HashMap<String, ClientServerConfig>,
+    pub after: HashMap<String, ClientServerConfig>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+/// A single value that differs between two versions of a server entry.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FieldChange {
+    /// JSON pointer into the server entry, e.g. `/env/API_KEY`.
+    pub path: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ServerChange {
+    #[serde(rename = "serverId")]
+    pub server_id: String,
+    pub kind: ChangeKind,
+    pub fields: Vec<FieldChange>,
+}
+
+fn load_entries(app_handle: &tauri::AppHandle) -> Result<Vec<HistoryEntry>> {
+    let store = app_handle.store(HISTORY_FILENAME)?;
+    match store.get(ENTRIES_KEY) {
+        Some(entries) => serde_json::from_value(entries).context("Failed to read config history"),
+        None => Ok(vec![]),
+    }
+}
+
+fn save_entries(app_handle: &tauri::AppHandle, entries: &[HistoryEntry]) -> Result<()> {
+    let store = app_handle.store(HISTORY_FILENAME)?;
+    store.set(ENTRIES_KEY, serde_json::to_value(entries)?);
+    Ok(())
+}
+
+/// Applies `change` to the target's config and journals it. Nothing is recorded when
+/// the change leaves the server entries as they were.
+pub fn apply_change(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+    source: ChangeSource,
+    change: impl FnOnce(&mut HashMap<String, ClientServerConfig>) -> Result<()>,
+) -> Result<()> {
+    let client = get_client(app_handle, target)?;
+    let servers = update_servers(app_handle, client.as_ref(), change)?;
+    record_change(
+        app_handle,
+        target,
+        &source,
+        None,
+        servers.before,
+        servers.after,
+    )
+}
+
+/// Adds an entry for a change that has already been written to `target`'s config.
+pub fn record_change(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+    source: &ChangeSource,
+    revert_of: Option<String>,
+    before: HashMap<String, ClientServerConfig>,
+    after: HashMap<String, ClientServerConfig>,
+) -> Result<()> {
+    if diff_servers(&before, &after).is_empty() {
+        return Ok(());
+    }
+    let client = get_client(app_handle, target)?;
+    let mut entries = load_entries(app_handle)?;
+    let mut timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64;
+    // Ids double as sort keys, so they must stay unique and increasing.
+    if let Some(last) = entries.last() {
+        timestamp = timestamp.max(last.timestamp + 1);
+    }
+    let entry = HistoryEntry {
+        id: timestamp.to_string(),
+        timestamp,
+        client_id: client.id().to_string(),
+        scope: target.scope.clone(),
+        config_path: client.config_path()?.to_string_lossy().to_string(),
+        operation: source.operation,
+        server_id: source.server_id.map(String::from),
+        trigger: source.trigger.to_string(),
+        revert_of,
+        before,
+        after,
+    };
+    debug!(
+        "Recording {:?} of {:?} in {} as {}",
+        entry.operation, entry.server_id, entry.client_id, entry.id
+    );
+    entries.push(entry);
+    let overflow = entries.len().saturating_sub(MAX_HISTORY_ENTRIES);
+    entries.drain(..overflow);
+    save_entries(app_handle, &entries)
+}
+
+fn find_entry(entries: &[HistoryEntry], entry_id: &str) -> Result<HistoryEntry> {
+    entries
+        .iter()
+        .find(|entry| entry.id == entry_id)
+        .cloned()
+        .ok_or_else(|| anyhow!("History entry {} not found", entry_id))
+}
+
+/// Journal entries for the target's config, newest first. Secret env values are
+/// masked; the journal keeps them only so that reverts can put them back.
+pub async fn get_config_history_function(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+) -> Result<Vec<HistoryEntry>> {
+    let client_id = get_client(app_handle, target)?.id().to_string();
+    let mut entries: Vec<HistoryEntry> = load_entries(app_handle)?
+        .into_iter()
+        .filter(|entry| entry.client_id == client_id && entry.scope == target.scope)
+        .collect();
+    entries.reverse();
+    for entry in entries.iter_mut() {
+        for servers in [&mut entry.before, &mut entry.after] {
+            for (server_id, server_config) in servers.iter_mut() {
+                let is_secret = secret_env_check(app_handle, server_id, Some(server_config));
+                mask_env(&mut server_config.env, is_secret);
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// Compares the versions left by two entries of the same config. Without
+/// `from_id`, the version `to_id` was applied on top of is used, which gives the
+/// change that entry made.
+pub async fn diff_config_versions_function(
+    app_handle: &tauri::AppHandle,
+    from_id: Option<String>,
+    to_id: &str,
+) -> Result<Vec<ServerChange>> {
+    let entries = load_entries(app_handle)?;
+    let to = find_entry(&entries, to_id)?;
+    let from = match from_id {
+        Some(from_id) => {
+            let from = find_entry(&entries, &from_id)?;
+            if from.client_id != to.client_id || from.scope != to.scope {
+                bail!(
+                    "History entries {} and {} belong to different configs",
+                    from.id,
+                    to.id
+                );
+            }
+            from.after
+        }
+        None => to.before,
+    };
+    let mut changes = diff_servers(&from, &to.after);
+    mask_changes(app_handle, &mut changes, &from, &to.after);
+    Ok(changes)
+}
+
+/// Undoes what a single entry did to the servers it touched. Servers changed again
+/// since then are not overwritten; those later changes have to be reverted first.
+pub async fn revert_config_change_function(
+    app_handle: &tauri::AppHandle,
+    entry_id: &str,
+) -> Result<()> {
+    let _guard = lock_mutations(app_handle);
+    let entry = find_entry(&load_entries(app_handle)?, entry_id)?;
+    let target = ClientTarget::new(Some(entry.client_id.clone()), Some(entry.scope.clone()));
+    let client = get_client(app_handle, &target)?;
+    let mut saved = SavedEntries::default();
+    let written = update_servers(app_handle, client.as_ref(), |servers| {
+        revert_servers(&entry, servers, |server_id, server_config| {
+            if !Launcher::runs_launcher(server_config) {
+                return Ok(server_config.clone());
+            }
+            let lost = lost_secrets(app_handle, server_id, server_config)?;
+            if !lost.is_empty() {
+                bail!(
+                    "{} lost its secrets ({}) when it was removed; install it again instead",
+                    server_id,
+                    lost.join(", ")
+                );
+            }
+            rebuild_server_entry(app_handle, &target, server_id, server_config, &mut saved)
+        })
+    });
+    let servers = saved.finish(written)?;
+    record_change(
+        app_handle,
+        &target,
+        &ChangeSource {
+            operation: Operation::Revert,
+            server_id: entry.server_id.as_deref(),
+            trigger: "revert_config_change",
+        },
+        Some(entry.id),
+        servers.before,
+        servers.after,
+    )
+}
+
+/// Puts the servers `entry` changed back the way they were before it, through
+/// `restore` for those it changed or removed.
+fn revert_servers(
+    entry: &HistoryEntry,
+    servers: &mut HashMap<String, ClientServerConfig>,
+    mut restore: impl FnMut(&str, &ClientServerConfig) -> Result<ClientServerConfig>,
+) -> Result<()> {
+    for change in diff_servers(&entry.before, &entry.after) {
+        let server_id = &change.server_id;
+        if to_value_opt(servers.get(server_id)) != to_value_opt(entry.after.get(server_id)) {
+            bail!(
+                "{} has changed since this operation; revert the later changes first",
+                server_id
+            );
+        }
+        match entry.before.get(server_id) {
+            Some(server_config) => {
+                let server_config = restore(server_id, server_config)?;
+                servers.insert(server_id.clone(), server_config);
+            }
+            None => {
+                servers.remove(server_id);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Structured difference between two versions of a servers map, sorted by server id.
+pub fn diff_servers(
+    before: &HashMap<String, ClientServerConfig>,
+    after: &HashMap<String, ClientServerConfig>,
+) -> Vec<ServerChange> {
+    let server_ids: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    server_ids
+        .into_iter()
+        .filter_map(|server_id| {
+            let old = to_value_opt(before.get(server_id));
+            let new = to_value_opt(after.get(server_id));
+            let kind = match (&old, &new) {
+                (None, Some(_)) => ChangeKind::Added,
+                (Some(_), None) => ChangeKind::Removed,
+                (Some(old), Some(new)) if old != new => ChangeKind::Modified,
+                _ => return None,
+            };
+            let mut fields = vec![];
+            diff_values("", old.as_ref(), new.as_ref(), &mut fields);
+            Some(ServerChange {
+                server_id: server_id.clone(),
+                kind,
+                fields,
+            })
+        })
+        .collect()
+}
+
+fn to_value_opt(server_config: Option<&ClientServerConfig>) -> Option<Value> {
+    server_config.and_then(|server_config| serde_json::to_value(server_config).ok())
+}
+
+/// Walks both values in step, descending into objects. Arrays are compared whole
+/// since positional diffs of argument lists read poorly.
+fn diff_values(
+    path: &str,
+    before: Option<&Value>,
+    after: Option<&Value>,
+    out: &mut Vec<FieldChange>,
+) {
+    if before == after {
+        return;
+    }
+    if let (Some(Value::Object(before)), Some(Value::Object(after))) = (before, after) {
+        let keys: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+        for key in keys {
+            let child = format!("{}/{}", path, key.replace('~', "~0").replace('/', "~1"));
+            diff_values(&child, before.get(key), after.get(key), out);
+        }
+        return;
+    }
+    if path.is_empty() {
+        // A whole entry appeared or disappeared; list its top level fields.
+        let object = before.or(after).and_then(Value::as_object);
+        if let Some(object) = object {
+            for (key, value) in object {
+                let child = format!("/{}", key.replace('~', "~0").replace('/', "~1"));
+                out.push(FieldChange {
+                    path: child,
+                    before: before.map(|_| value.clone()),
+                    after: after.map(|_| value.clone()),
+                });
+            }
+            return;
+        }
+    }
+    out.push(FieldChange {
+        path: path.to_string(),
+        before: before.cloned(),
+        after: after.cloned(),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn servers(value: Value) -> HashMap<String, ClientServerConfig> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn history_entry(before: Value, after: Value) -> HistoryEntry {
+        HistoryEntry {
+            id: "1".to_string(),
+            timestamp: 1,
+            client_id: "claude".to_string(),
+            scope: ConfigScope::default(),
+            config_path: "config.json".to_string(),
+            operation: Operation::Update,
+            server_id: None,
+            trigger: "test".to_string(),
+            revert_of: None,
+            before: servers(before),
+            after: servers(after),
+        }
+    }
+
+    #[test]
+    fn diffs_list_fields_by_server() {
+        let before = servers(json!({
+            "kept": { "command": "npx" },
+            "gone": { "command": "uvx", "args": ["gone"] },
+            "edited": { "command": "npx", "env": { "A": "1", "B/C": "2" } },
+        }));
+        let after = servers(json!({
+            "kept": { "command": "npx" },
+            "added": { "command": "node" },
+            "edited": { "command": "npx", "env": { "A": "1", "B/C": "3" } },
+        }));
+        let changes = diff_servers(&before, &after);
+        let summary: Vec<(&str, ChangeKind, Vec<&str>)> = changes
+            .iter()
+            .map(|change| {
+                let paths = change.fields.iter().map(|field| field.path.as_str());
+                (
+                    change.server_id.as_str(),
+                    change.kind.clone(),
+                    paths.collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("added", ChangeKind::Added, vec!["/command"]),
+                ("edited", ChangeKind::Modified, vec!["/env/B~1C"]),
+                ("gone", ChangeKind::Removed, vec!["/args", "/command"]),
+            ]
+        );
+        let edited = &changes[1].fields[0];
+        assert_eq!(edited.before, Some(json!("2")));
+        assert_eq!(edited.after, Some(json!("3")));
+        assert!(diff_servers(&before, &before).is_empty());
+    }
+
+    #[test]
+    fn reverts_put_back_what_an_entry_changed() {
+        let entry = history_entry(
+            json!({
+                "edited": { "command": "npx", "args": ["v1"] },
+                "removed": { "command": "uvx" },
+            }),
+            json!({
+                "edited": { "command": "npx", "args": ["v2"] },
+                "added": { "command": "node" },
+            }),
+        );
+        let mut current = servers(json!({
+            "edited": { "command": "npx", "args": ["v2"] },
+            "added": { "command": "node" },
+            "other": { "command": "python" },
+        }));
+        let mut restored = vec![];
+        revert_servers(&entry, &mut current, |server_id, server_config| {
+            restored.push(server_id.to_string());
+            Ok(server_config.clone())
+        })
+        .unwrap();
+        assert_eq!(restored, vec!["edited", "removed"]);
+        assert_eq!(
+            serde_json::to_value(&current).unwrap(),
+            json!({
+                "edited": { "command": "npx", "args": ["v1"] },
+                "removed": { "command": "uvx" },
+                "other": { "command": "python" },
+            })
+        );
+    }
+
+    #[test]
+    fn reverts_refuse_servers_changed_since() {
+        let entry = history_entry(
+            json!({ "edited": { "command": "npx", "args": ["v1"] } }),
+            json!({ "edited": { "command": "npx", "args": ["v2"] } }),
+        );
+        let mut current = servers(json!({ "edited": { "command": "npx", "args": ["v3"] } }));
+        let error = revert_servers(&entry, &mut current, |_, server_config| {
+            Ok(server_config.clone())
+        })
+        .unwrap_err();
+        assert!(error.to_string().contains("revert the later changes first"));
+        assert_eq!(current["edited"].args, vec!["v3"]);
+    }
+
+    #[test]
+    fn reverts_stop_when_a_server_cannot_be_restored() {
+        let entry = history_entry(json!({ "managed": { "command": "npx" } }), json!({}));
+        let mut current = HashMap::new();
+        let result = revert_servers(&entry, &mut current, |server_id, _| {
+            bail!("{} lost its secrets", server_id)
+        });
+        assert!(result.is_err());
+        assert!(current.is_empty());
+    }
+}
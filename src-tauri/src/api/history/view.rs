@@ -0,0 +1,39 @@
+use super::core::{
+    diff_config_versions_function, get_config_history_function, revert_config_change_function,
+    HistoryEntry, ServerChange,
+};
+use crate::clients::{ClientTarget, ConfigScope};
+use crate::error::AppError;
+
+#[tauri::command]
+pub async fn get_config_history(
+    app_handle: tauri::AppHandle,
+    client_id: Option<String>,
+    scope: Option<ConfigScope>,
+) -> Result<Vec<HistoryEntry>, AppError> {
+    let target = ClientTarget::new(client_id, scope);
+    get_config_history_function(&app_handle, &target)
+        .await
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn diff_config_versions(
+    app_handle: tauri::AppHandle,
+    from_id: Option<String>,
+    to_id: &str,
+) -> Result<Vec<ServerChange>, AppError> {
+    diff_config_versions_function(&app_handle, from_id, to_id)
+        .await
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn revert_config_change(
+    app_handle: tauri::AppHandle,
+    entry_id: &str,
+) -> Result<(), AppError> {
+    revert_config_change_function(&app_handle, entry_id)
+        .await
+        .map_err(AppError::from)
+}
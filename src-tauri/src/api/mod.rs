@@ -1,2 +1,9 @@
+pub mod backups;
+pub mod bundles;
+pub mod clients;
 pub mod dependency;
+pub mod history;
+pub mod profiles;
+pub mod secrets;
 pub mod servers;
+pub mod watcher;
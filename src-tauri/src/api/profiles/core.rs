@@ -0,0 +1,246 @@
+use crate::api::history::core::{apply_change, ChangeSource, Operation};
+use crate::api::servers::core::{
+    park_servers, rebuild_server_entry, refuse_unmanaged, unpark_server, SavedEntries,
+};
+use crate::clients::{
+    get_client, lock_mutations, read_servers, ClientServerConfig, ClientTarget, MCPHUB_CREATOR,
+};
+use crate::APP_STATE_FILENAME;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri_plugin_store::StoreExt;
+
+const PROFILES_KEY: &str = "profiles";
+
+/// A named set of MCPHub servers with their env values and arguments, which can be
+/// written into any client at once.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Profile {
+    name: String,
+    /// Milliseconds since the Unix epoch.
+    #[serde(rename = "createdAt")]
+    created_at: u64,
+    servers: HashMap<String, ClientServerConfig>,
+}
+
+fn load_profiles(app_handle: &tauri::AppHandle) -> Result<Vec<Profile>> {
+    let store = app_handle.store(APP_STATE_FILENAME)?;
+    match store.get(PROFILES_KEY) {
+        Some(profiles) => Ok(serde_json::from_value(profiles)?),
+        None => Ok(vec![]),
+    }
+}
+
+fn save_profiles(app_handle: &tauri::AppHandle, profiles: &[Profile]) -> Result<()> {
+    let store = app_handle.store(APP_STATE_FILENAME)?;
+    store.set(PROFILES_KEY, serde_json::to_value(profiles)?);
+    Ok(())
+}
+
+fn find_profile(profiles: &[Profile], name: &str) -> Result<Profile> {
+    profiles
+        .iter()
+        .find(|profile| profile.name == name)
+        .cloned()
+        .with_context(|| format!("Profile not found: {}", name))
+}
+
+fn add_profile(
+    app_handle: &tauri::AppHandle,
+    mut profiles: Vec<Profile>,
+    name: &str,
+    servers: HashMap<String, ClientServerConfig>,
+) -> Result<Profile> {
+    let name = name.trim();
+    if name.is_empty() {
+        bail!("Profile name can't be empty");
+    }
+    if profiles.iter().any(|profile| profile.name == name) {
+        bail!("A profile named {} already exists", name);
+    }
+    let profile = Profile {
+        name: name.to_string(),
+        created_at: SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64,
+        servers,
+    };
+    profiles.push(profile.clone());
+    save_profiles(app_handle, &profiles)?;
+    Ok(profile)
+}
+
+pub async fn load_all_profiles(app_handle: &tauri::AppHandle) -> Result<Vec<Profile>> {
+    load_profiles(app_handle)
+}
+
+/// Saves the MCPHub servers currently configured for `target` as a new profile,
+/// limited to `server_ids` when given.
+pub async fn create_profile_function(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+    name: &str,
+    server_ids: Option<Vec<String>>,
+) -> Result<Profile> {
+    let _guard = lock_mutations(app_handle);
+    let configured = read_servers(get_client(app_handle, target)?.as_ref())?;
+    let mut servers = HashMap::new();
+    for (server_id, server_config) in configured {
+        let wanted = match &server_ids {
+            Some(server_ids) => server_ids.contains(&server_id),
+            None => true,
+        };
+        if wanted && server_config.command_creator == MCPHUB_CREATOR {
+            servers.insert(server_id, server_config);
+        }
+    }
+    if let Some(server_ids) = &server_ids {
+        if let Some(missing) = server_ids.iter().find(|id| !servers.contains_key(*id)) {
+            bail!("{} is not an MCPHub server of this client", missing);
+        }
+    }
+    add_profile(app_handle, load_profiles(app_handle)?, name, servers)
+}
+
+pub async fn clone_profile_function(
+    app_handle: &tauri::AppHandle,
+    name: &str,
+    new_name: &str,
+) -> Result<Profile> {
+    let _guard = lock_mutations(app_handle);
+    let profiles = load_profiles(app_handle)?;
+    let source = find_profile(&profiles, name)?;
+    add_profile(app_handle, profiles, new_name, source.servers)
+}
+
+pub async fn delete_profile_function(app_handle: &tauri::AppHandle, name: &str) -> Result<()> {
+    let _guard = lock_mutations(app_handle);
+    let mut profiles = load_profiles(app_handle)?;
+    let count = profiles.len();
+    profiles.retain(|profile| profile.name != name);
+    if profiles.len() == count {
+        bail!("Profile not found: {}", name);
+    }
+    save_profiles(app_handle, &profiles)
+}
+
+/// Makes the MCPHub servers of `target` exactly the profile's members. Entries MCPHub
+/// didn't write stay untouched, so a member whose id one of them uses is refused.
+/// MCPHub servers the profile leaves out are disabled rather than removed, so their
+/// secrets survive and they can be enabled again.
+pub async fn activate_profile_function(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+    name: &str,
+) -> Result<()> {
+    let _guard = lock_mutations(app_handle);
+    let profile = find_profile(&load_profiles(app_handle)?, name)?;
+    let source = ChangeSource {
+        operation: Operation::ActivateProfile,
+        server_id: None,
+        trigger: "activate_profile",
+    };
+    let mut saved = SavedEntries::default();
+    let mut dropped = vec![];
+    let written = apply_change(app_handle, target, source, |servers| {
+        dropped = activate_servers(servers, &profile.servers, |server_id, server_config| {
+            rebuild_server_entry(app_handle, target, server_id, server_config, &mut saved)
+        })?;
+        Ok(())
+    });
+    saved.finish(written)?;
+    park_servers(app_handle, target, dropped)?;
+    for server_id in profile.servers.keys() {
+        unpark_server(app_handle, target, server_id)?;
+    }
+    Ok(())
+}
+
+/// Replaces the MCPHub servers in `servers` with `members`, as `rebuild` returns them.
+/// Returns the MCPHub entries taken out, sorted by id.
+fn activate_servers(
+    servers: &mut HashMap<String, ClientServerConfig>,
+    members: &HashMap<String, ClientServerConfig>,
+    mut rebuild: impl FnMut(&str, &ClientServerConfig) -> Result<ClientServerConfig>,
+) -> Result<Vec<(String, ClientServerConfig)>> {
+    refuse_unmanaged(servers, members.keys())?;
+    let mut dropped_ids: Vec<String> = servers
+        .iter()
+        .filter(|(server_id, server_config)| {
+            server_config.command_creator == MCPHUB_CREATOR && !members.contains_key(*server_id)
+        })
+        .map(|(server_id, _)| server_id.clone())
+        .collect();
+    dropped_ids.sort();
+    let dropped = dropped_ids
+        .into_iter()
+        .filter_map(|server_id| {
+            let server_config = servers.remove(&server_id)?;
+            Some((server_id, server_config))
+        })
+        .collect();
+    // Members were saved from any client, so each is rebuilt for this one.
+    for (server_id, server_config) in members {
+        let server_config = rebuild(server_id, server_config)?;
+        servers.insert(server_id.clone(), server_config);
+    }
+    Ok(dropped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn servers(value: serde_json::Value) -> HashMap<String, ClientServerConfig> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn activation_takes_out_the_servers_the_profile_leaves_out() {
+        let mut current = servers(json!({
+            "github": { "command": "npx", "args": ["old"], "commandCreator": "MCPHub" },
+            "slack": { "command": "npx", "commandCreator": "MCPHub" },
+            "fetch": { "command": "uvx", "commandCreator": "MCPHub" },
+            "mine": { "command": "python" },
+        }));
+        let members = servers(json!({
+            "github": { "command": "npx", "args": ["new"], "commandCreator": "MCPHub" },
+            "time": { "command": "uvx", "commandCreator": "MCPHub" },
+        }));
+        let mut rebuilt = vec![];
+        let dropped = activate_servers(&mut current, &members, |server_id, server_config| {
+            rebuilt.push(server_id.to_string());
+            Ok(server_config.clone())
+        })
+        .unwrap();
+        rebuilt.sort();
+        assert_eq!(rebuilt, vec!["github", "time"]);
+        let dropped_ids: Vec<&str> = dropped.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(dropped_ids, vec!["fetch", "slack"]);
+        assert_eq!(dropped[0].1.command, "uvx");
+        let mut ids: Vec<&String> = current.keys().collect();
+        ids.sort();
+        assert_eq!(ids, vec!["github", "mine", "time"]);
+        assert_eq!(current["github"].args, vec!["new"]);
+    }
+
+    #[test]
+    fn activation_refuses_members_configured_by_hand() {
+        let mut current = servers(json!({
+            "github": { "command": "docker" },
+            "slack": { "command": "npx", "commandCreator": "MCPHub" },
+        }));
+        let members = servers(json!({
+            "github": { "command": "npx", "commandCreator": "MCPHub" },
+        }));
+        let error = activate_servers(&mut current, &members, |_, server_config| {
+            Ok(server_config.clone())
+        })
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("github is configured outside MCPHub"));
+        assert_eq!(current.len(), 2);
+    }
+}
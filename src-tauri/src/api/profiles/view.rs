@@ -0,0 +1,56 @@
+use super::core::{
+    activate_profile_function, clone_profile_function, create_profile_function,
+    delete_profile_function, load_all_profiles, Profile,
+};
+use crate::clients::{ClientTarget, ConfigScope};
+use crate::error::AppError;
+
+#[tauri::command]
+pub async fn get_profiles(app_handle: tauri::AppHandle) -> Result<Vec<Profile>, AppError> {
+    load_all_profiles(&app_handle).await.map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn create_profile(
+    app_handle: tauri::AppHandle,
+    client_id: Option<String>,
+    scope: Option<ConfigScope>,
+    name: &str,
+    server_ids: Option<Vec<String>>,
+) -> Result<Profile, AppError> {
+    let target = ClientTarget::new(client_id, scope);
+    create_profile_function(&app_handle, &target, name, server_ids)
+        .await
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn clone_profile(
+    app_handle: tauri::AppHandle,
+    name: &str,
+    new_name: &str,
+) -> Result<Profile, AppError> {
+    clone_profile_function(&app_handle, name, new_name)
+        .await
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn delete_profile(app_handle: tauri::AppHandle, name: &str) -> Result<(), AppError> {
+    delete_profile_function(&app_handle, name)
+        .await
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn activate_profile(
+    app_handle: tauri::AppHandle,
+    client_id: Option<String>,
+    scope: Option<ConfigScope>,
+    name: &str,
+) -> Result<(), AppError> {
+    let target = ClientTarget::new(client_id, scope);
+    activate_profile_function(&app_handle, &target, name)
+        .await
+        .map_err(AppError::from)
+}
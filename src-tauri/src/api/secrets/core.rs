@@ -0,0 +1,73 @@
+use crate::api::servers::core::{catalog_server, is_catalog_server, ConfigChangeResult};
+use crate::clients::{get_client, lock_mutations, read_servers, ClientTarget};
+use crate::error::AppError;
+use crate::launcher::Launcher;
+use crate::utils::secrets::{delete_secret, get_secret, set_secret};
+use anyhow::{anyhow, Result};
+
+/// The launcher of server `server_id` and the secret store account it reads env
+/// variable `key` from.
+fn find_secret(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+    server_id: &str,
+    key: &str,
+) -> Result<(Launcher, String)> {
+    let servers = read_servers(get_client(app_handle, target)?.as_ref())?;
+    let server_config = servers
+        .get(server_id)
+        .ok_or_else(|| AppError::ServerNotInstalled {
+            server_id: server_id.to_string(),
+        })?;
+    Launcher::from_entry(server_config)
+        .and_then(|launcher| {
+            let account = launcher.spec.secrets.get(key)?.clone();
+            Some((launcher, account))
+        })
+        .ok_or_else(|| anyhow!("{} of {} is not kept in the secret store", key, server_id))
+}
+
+/// Replaces the stored value of secret `key`. Running servers keep the old value
+/// until they are restarted.
+pub async fn rotate_server_secret_function(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+    server_id: &str,
+    key: &str,
+    value: &str,
+) -> Result<ConfigChangeResult> {
+    let _guard = lock_mutations(app_handle);
+    let (launcher, account) = find_secret(app_handle, target, server_id, key)?;
+    let catalog_id = &launcher.spec.catalog_id;
+    if is_catalog_server(app_handle, catalog_id)? {
+        catalog_server(app_handle, catalog_id)?.validate_env_value(key, value)?;
+    }
+    set_secret(&account, value)?;
+    ConfigChangeResult::for_target(app_handle, target)
+}
+
+pub async fn reveal_server_secret_function(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+    server_id: &str,
+    key: &str,
+) -> Result<String> {
+    let (_, account) = find_secret(app_handle, target, server_id, key)?;
+    get_secret(&account)?
+        .ok_or_else(|| anyhow!("{} of {} is missing from the secret store", key, server_id))
+}
+
+/// Deletes secret `key` from the secret store and stops passing it to the server.
+pub async fn delete_server_secret_function(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+    server_id: &str,
+    key: &str,
+) -> Result<ConfigChangeResult> {
+    let _guard = lock_mutations(app_handle);
+    let (mut launcher, account) = find_secret(app_handle, target, server_id, key)?;
+    launcher.spec.secrets.remove(key);
+    launcher.save()?;
+    delete_secret(&account)?;
+    ConfigChangeResult::for_target(app_handle, target)
+}
@@ -0,0 +1,49 @@
+use super::core::{
+    delete_server_secret_function, reveal_server_secret_function, rotate_server_secret_function,
+};
+use crate::api::servers::core::ConfigChangeResult;
+use crate::clients::{ClientTarget, ConfigScope};
+use crate::error::AppError;
+
+#[tauri::command]
+pub async fn rotate_server_secret(
+    app_handle: tauri::AppHandle,
+    client_id: Option<String>,
+    scope: Option<ConfigScope>,
+    server_id: &str,
+    key: &str,
+    value: &str,
+) -> Result<ConfigChangeResult, AppError> {
+    let target = ClientTarget::new(client_id, scope);
+    rotate_server_secret_function(&app_handle, &target, server_id, key, value)
+        .await
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn reveal_server_secret(
+    app_handle: tauri::AppHandle,
+    client_id: Option<String>,
+    scope: Option<ConfigScope>,
+    server_id: &str,
+    key: &str,
+) -> Result<String, AppError> {
+    let target = ClientTarget::new(client_id, scope);
+    reveal_server_secret_function(&app_handle, &target, server_id, key)
+        .await
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn delete_server_secret(
+    app_handle: tauri::AppHandle,
+    client_id: Option<String>,
+    scope: Option<ConfigScope>,
+    server_id: &str,
+    key: &str,
+) -> Result<ConfigChangeResult, AppError> {
+    let target = ClientTarget::new(client_id, scope);
+    delete_server_secret_function(&app_handle, &target, server_id, key)
+        .await
+        .map_err(AppError::from)
+}
@@ -1,11 +1,25 @@
-use crate::utils::os::get_home;
-use std::borrow::Cow;
+use crate::api::history::core::{
+    apply_change, diff_servers, ChangeSource, FieldChange, Operation, ServerChange,
+};
+use crate::clients::{
+    get_client, lock_mutations, needs_restart, preview_servers, read_servers, ClientServerConfig,
+    ClientTarget, ConfigScope, MCPHUB_CREATOR,
+};
+use crate::error::{AppError, EnvFieldError};
+use crate::launcher::{app_state_path, LaunchSpec, Launcher};
 use crate::APP_STATE_FILENAME;
-use log::{debug, error};
+use crate::utils::secrets::{
+    delete_secret, get_secret, is_secret_key, mask_env, secret_account, set_secret,
+    SECRET_MASK,
+};
+use anyhow::{bail, Context, Result};
+use log::{debug, warn};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 use tauri_plugin_store::StoreExt;
-use shell_escape::escape;
 
 #[derive(Debug, Serialize, Deserialize)]
 struct BaseServer {
@@ -27,28 +41,163 @@ pub struct FrontendServer {
     base: BaseServer,
     #[serde(rename = "isInstalled", default)]
     is_installed: bool,
+    /// False while an installed server is parked by `disable_server`.
+    #[serde(default)]
+    enabled: bool,
     #[serde(default)]
     env: HashMap<String, String>,
+    /// What the catalog says about each env variable, with `secret` always filled in.
+    #[serde(default, rename = "envSchema")]
+    env_schema: BTreeMap<String, EnvVarSchema>,
     #[serde(default)]
     args: Vec<String>,
     #[serde(default)]
     guide: String,
-    #[serde(default, rename = "inputArg")]
-    input_arg: InputArg,
+    #[serde(default, rename = "inputArgs")]
+    input_args: Vec<InputArg>,
+    /// Every installed copy of this server, the one keyed by the catalog id first. The
+    /// fields above describe that first instance.
+    #[serde(default)]
+    instances: Vec<ServerInstance>,
+}
+
+/// One installed copy of a catalog server with its own env and input arguments.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ServerInstance {
+    /// Key of the instance in the client config.
+    id: String,
+    enabled: bool,
+    env: HashMap<String, String>,
+    #[serde(rename = "inputArgs")]
+    input_args: Vec<InputArg>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct SystemCommandInfo {
     command: String,
     args: Vec<String>,
-    #[serde(default, rename = "inputArg")]
-    input_arg: InputArg,
-    #[serde(default)]
-    env: HashMap<String, String>,
+    /// Arguments the user fills in, in the order they follow `args`.
+    #[serde(
+        default,
+        rename = "inputArgs",
+        alias = "inputArg",
+        deserialize_with = "deserialize_input_args"
+    )]
+    input_args: Vec<InputArg>,
+    #[serde(default, deserialize_with = "deserialize_env_schema")]
+    env: BTreeMap<String, EnvVarSchema>,
     #[serde(default)]
     guide: String,
 }
 
+/// What the catalog says about one env variable of a server. Older catalogs give just
+/// the default value, which reads as an optional variable with that default.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct EnvVarSchema {
+    #[serde(default)]
+    required: bool,
+    /// Whether the value goes to the secret store. When the catalog doesn't say, this
+    /// is guessed from the variable's name.
+    #[serde(default)]
+    secret: Option<bool>,
+    #[serde(default)]
+    description: String,
+    /// Regular expression the whole value must match.
+    #[serde(default)]
+    pattern: Option<EnvPattern>,
+    #[serde(default)]
+    default: Option<String>,
+    /// Page that explains how to get a value, such as an API key.
+    #[serde(default, rename = "helpUrl")]
+    help_url: Option<String>,
+}
+
+impl EnvVarSchema {
+    fn is_secret(&self, key: &str) -> bool {
+        self.secret.unwrap_or_else(|| is_secret_key(key))
+    }
+
+    /// Why `value` is not acceptable for variable `key`, if it isn't.
+    fn check(&self, key: &str, value: &str) -> Result<Option<String>> {
+        // The stored value was checked when it was set.
+        if value == SECRET_MASK {
+            return Ok(None);
+        }
+        if value.is_empty() {
+            return Ok(self.required.then(|| format!("{} is required", key)));
+        }
+        let Some(pattern) = &self.pattern else {
+            return Ok(None);
+        };
+        let regex = pattern
+            .regex
+            .clone()
+            .with_context(|| format!("The catalog pattern of {} is invalid", key))?;
+        if regex.is_match(value) {
+            return Ok(None);
+        }
+        let mut message = format!(
+            "{} does not match the expected format {}",
+            key, pattern.source
+        );
+        if !self.description.is_empty() {
+            message = format!("{} ({})", message, self.description);
+        }
+        Ok(Some(message))
+    }
+}
+
+/// A catalog pattern, compiled once when the catalog is read. One that doesn't compile
+/// only fails the variables it is for.
+#[derive(Debug, Clone)]
+struct EnvPattern {
+    source: String,
+    regex: Result<Regex, regex::Error>,
+}
+
+impl Serialize for EnvPattern {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.source.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for EnvPattern {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let source = String::deserialize(deserializer)?;
+        let regex = Regex::new(&format!("^(?:{})$", source));
+        Ok(EnvPattern { source, regex })
+    }
+}
+
+fn deserialize_env_schema<'de, D>(
+    deserializer: D,
+) -> Result<BTreeMap<String, EnvVarSchema>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum EnvVarEntry {
+        Default(String),
+        Schema(EnvVarSchema),
+    }
+
+    let entries = BTreeMap::<String, EnvVarEntry>::deserialize(deserializer)?;
+    Ok(entries
+        .into_iter()
+        .map(|(key, entry)| {
+            let schema = match entry {
+                EnvVarEntry::Default(default) => EnvVarSchema {
+                    default: Some(default),
+                    ..Default::default()
+                },
+                EnvVarEntry::Schema(schema) => schema,
+            };
+            (key, schema)
+        })
+        .collect())
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BackendServer {
     #[serde(flatten)]
@@ -57,6 +206,161 @@ pub struct BackendServer {
     command_info: SystemCommandInfo,
 }
 
+impl BackendServer {
+    /// Env of a new install: every variable the catalog lists, with its default.
+    fn default_env(&self) -> HashMap<String, String> {
+        self.command_info
+            .env
+            .iter()
+            .map(|(key, schema)| (key.clone(), schema.default.clone().unwrap_or_default()))
+            .collect()
+    }
+
+    /// Whether the value of env variable `key` goes to the secret store.
+    pub fn is_secret_env(&self, key: &str) -> bool {
+        match self.command_info.env.get(key) {
+            Some(schema) => schema.is_secret(key),
+            None => is_secret_key(key),
+        }
+    }
+
+    /// Secret variables the entry `server_config` of this server read from the secret
+    /// store whose values are gone. Removing a launcher deletes the secrets it reads,
+    /// so once it is gone every secret variable the catalog lists counts as lost.
+    fn lost_secrets(&self, server_config: &ClientServerConfig) -> Result<Vec<String>> {
+        if !Launcher::runs_launcher(server_config) {
+            return Ok(vec![]);
+        }
+        let Some(launcher) = Launcher::from_entry(server_config) else {
+            return Ok(self
+                .command_info
+                .env
+                .keys()
+                .filter(|key| self.is_secret_env(key) && !server_config.env.contains_key(*key))
+                .cloned()
+                .collect());
+        };
+        let mut lost = vec![];
+        for (key, account) in &launcher.spec.secrets {
+            if get_secret(account)?.is_none() {
+                lost.push(key.clone());
+            }
+        }
+        Ok(lost)
+    }
+
+    /// Checks the new value of a single variable, such as a replaced secret.
+    pub fn validate_env_value(&self, key: &str, value: &str) -> Result<()> {
+        let message = match self.command_info.env.get(key) {
+            Some(schema) => schema.check(key, value)?,
+            None => None,
+        };
+        let fields = message
+            .map(|message| EnvFieldError {
+                key: key.to_string(),
+                message,
+            })
+            .into_iter()
+            .collect();
+        self.env_fields_result(fields)
+    }
+
+    /// Checks `env` against the catalog's schema, failing with a message for every
+    /// variable that is missing or malformed. Variables the catalog doesn't list pass,
+    /// and so do secrets unless `check_secrets` is set.
+    fn validate_env(&self, env: &HashMap<String, String>, check_secrets: bool) -> Result<()> {
+        let mut fields = vec![];
+        for (key, schema) in &self.command_info.env {
+            if !check_secrets && schema.is_secret(key) {
+                continue;
+            }
+            let value = env.get(key).map(String::as_str).unwrap_or_default();
+            if let Some(message) = schema.check(key, value)? {
+                fields.push(EnvFieldError {
+                    key: key.clone(),
+                    message,
+                });
+            }
+        }
+        self.env_fields_result(fields)
+    }
+
+    fn env_fields_result(&self, fields: Vec<EnvFieldError>) -> Result<()> {
+        if fields.is_empty() {
+            return Ok(());
+        }
+        Err(AppError::EnvInvalid {
+            server_id: self.base.id.clone(),
+            fields,
+        }
+        .into())
+    }
+
+    /// The catalog's input arguments with the given values, by argument name. Arguments
+    /// left out keep the catalog's values.
+    pub fn input_args(
+        &self,
+        values: Option<BTreeMap<String, Vec<String>>>,
+    ) -> Result<Vec<InputArg>> {
+        let mut values = values.unwrap_or_default();
+        let mut input_args = self.command_info.input_args.clone();
+        for input_arg in &mut input_args {
+            if let Some(value) = values.remove(&input_arg.name) {
+                input_arg.value = value;
+            }
+            input_arg.check()?;
+        }
+        if let Some(name) = values.keys().next() {
+            bail!("{} has no input argument named {}", self.base.id, name);
+        }
+        Ok(input_args)
+    }
+
+    /// Values given when servers took a single input argument, as values of the first.
+    pub fn legacy_input_args(&self, values: Vec<String>) -> BTreeMap<String, Vec<String>> {
+        self.command_info
+            .input_args
+            .first()
+            .map(|input_arg| (input_arg.name.clone(), values))
+            .into_iter()
+            .collect()
+    }
+
+    /// The command that runs the server with the given input arguments, using the
+    /// runtimes `runtime` picks. Without `runtime`, the program is left to PATH.
+    pub fn argv(
+        &self,
+        input_args: &[InputArg],
+        runtime: Option<&RuntimeSettings>,
+    ) -> Result<ServerArgv> {
+        let program = self.command_info.command.clone();
+        let mut args = self.command_info.args.clone();
+        for input_arg in input_args {
+            input_arg.push_args(&mut args);
+        }
+        let runtime_dir = match runtime {
+            Some(runtime) => runtime.runtime_dir(&program)?.map(PathBuf::from),
+            None => None,
+        };
+        Ok(ServerArgv {
+            program,
+            args,
+            runtime_dir,
+        })
+    }
+}
+
+/// A server command as an argument vector. Every argument reaches the program exactly
+/// as it is here; none of them is joined into a string that a shell parses again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerArgv {
+    pub program: String,
+    /// Catalog arguments followed by those of the input arguments, one value each.
+    pub args: Vec<String>,
+    /// Directory of the runtime that provides `program`, to go in front of PATH.
+    pub runtime_dir: Option<PathBuf>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
 enum ArgClass {
     #[default]
@@ -64,6 +368,8 @@ enum ArgClass {
     Select,
     FilePath,
     DirectoryPath,
+    /// A flag with no value, passed when the value is `true`.
+    Switch,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
@@ -73,12 +379,18 @@ enum ArgumentMultiplicity {
     Multiple,
 }
 
+/// An argument the user fills in. It is passed as its values alone, or with each
+/// value after `flag` when it has one.
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct InputArg {
     #[serde(default)]
     name: String,
     #[serde(default)]
     description: String,
+    /// Flag such as `--db` that goes before each value. One ending in `=`, such as
+    /// `--db=`, is joined with the value into a single argument.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    flag: Option<String>,
     #[serde(default)]
     class: ArgClass,
     #[serde(default)]
@@ -87,263 +399,1999 @@ pub struct InputArg {
     value: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ClientServerConfig {
-    #[serde(default)]
-    command: String,
-    args: Vec<String>,
-    #[serde(default)]
-    env: HashMap<String, String>,
-    #[serde(rename = "commandCreator", default)]
-    command_creator: String,
-    #[serde(rename = "inputArg", default)]
-    input_arg: InputArg,
-}
+impl InputArg {
+    /// An argument known only by its name and values, such as one read back from a
+    /// launcher.
+    pub fn with_values(name: String, value: Vec<String>) -> Self {
+        InputArg {
+            name,
+            value,
+            ..Default::default()
+        }
+    }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ClientConfig {
-    #[serde(rename = "mcpServers", default)]
-    mcp_servers: HashMap<String, ClientServerConfig>,
-    #[serde(flatten, default)]
-    other_fields: HashMap<String, serde_json::Value>,
-}
+    /// Whether this is the placeholder older catalogs give servers with no input argument.
+    fn is_empty(&self) -> bool {
+        self.name.is_empty() && self.value.is_empty()
+    }
 
-impl ClientConfig {
-    fn config_path() -> std::path::PathBuf {
-        #[cfg(target_os = "macos")]
-        {
-            get_home()
-                .unwrap()
-                .join("Library/Application Support/Claude/claude_desktop_config.json")
+    fn check(&self) -> Result<()> {
+        if matches!(self.multiplicity, ArgumentMultiplicity::Single) && self.value.len() > 1 {
+            bail!("{} takes a single value", self.name);
         }
-        #[cfg(target_os = "windows")]
-        {
-            let appdata = std::env::var("APPDATA").unwrap();
-            std::path::PathBuf::from(appdata)
-                .join("Claude")
-                .join("claude_desktop_config.json")
-        }
-    }
-
-    fn load() -> Self {
-        let config_path = Self::config_path();
-        let config = match std::fs::read_to_string(config_path) {
-            Ok(content) => content,
-            Err(_) => {
-                debug!("Config file not found, returning empty HashMap");
-                return ClientConfig {
-                    mcp_servers: HashMap::new(),
-                    other_fields: HashMap::new(),
-                };
+        if matches!(self.class, ArgClass::Switch) {
+            if self.flag.is_none() {
+                bail!("Switch {} has no flag in the catalog", self.name);
+            }
+            if let Some(value) = self
+                .value
+                .iter()
+                .find(|value| *value != "true" && *value != "false")
+            {
+                bail!(
+                    "{} is a switch and takes true or false, not {}",
+                    self.name,
+                    value
+                );
             }
+        }
+        Ok(())
+    }
+
+    /// Appends this argument to `args`. Empty values, such as rows the user added and
+    /// left blank, are skipped.
+    fn push_args(&self, args: &mut Vec<String>) {
+        let values = self.value.iter().filter(|value| !value.is_empty());
+        let Some(flag) = &self.flag else {
+            args.extend(values.cloned());
+            return;
         };
-        debug!("ClientConfig loaded config");
-        let config: ClientConfig = serde_json::from_str(&config).unwrap();
-        debug!("ClientConfig parsed config");
-        config
+        if matches!(self.class, ArgClass::Switch) {
+            if self.value.iter().any(|value| value == "true") {
+                args.push(flag.clone());
+            }
+            return;
+        }
+        for value in values {
+            if flag.ends_with('=') {
+                args.push(format!("{}{}", flag, value));
+            } else {
+                args.push(flag.clone());
+                args.push(value.clone());
+            }
+        }
     }
+}
+
+/// Values of `input_args` by argument name.
+pub fn input_arg_values(input_args: &[InputArg]) -> BTreeMap<String, Vec<String>> {
+    input_args
+        .iter()
+        .map(|input_arg| (input_arg.name.clone(), input_arg.value.clone()))
+        .collect()
+}
 
-    fn save(&self) {
-        let config_path = Self::config_path();
-        let config_str = serde_json::to_string_pretty(&self).unwrap();
-        std::fs::write(config_path.clone(), config_str).unwrap();
+/// Reads a list of input arguments, or the single one older catalogs and configs give.
+pub fn deserialize_input_args<'de, D>(deserializer: D) -> Result<Vec<InputArg>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum InputArgs {
+        List(Vec<InputArg>),
+        Single(InputArg),
     }
+
+    Ok(match InputArgs::deserialize(deserializer)? {
+        InputArgs::List(input_args) => input_args,
+        InputArgs::Single(input_arg) if input_arg.is_empty() => vec![],
+        InputArgs::Single(input_arg) => vec![input_arg],
+    })
 }
 
-fn get_servers_from_store<T: for<'de> Deserialize<'de>>(app_handle: &tauri::AppHandle) -> Vec<T> {
-    let store = app_handle.store(APP_STATE_FILENAME).unwrap();
-    let raw_servers_str: String = serde_json::from_value(
-        store
-            .get("servers")
-            .expect("Failed to get servers from store"),
-    )
-    .unwrap();
-    let servers: Vec<T> = serde_json::from_str(&raw_servers_str).unwrap();
-    servers
+fn get_servers_from_store<T: for<'de> Deserialize<'de>>(
+    app_handle: &tauri::AppHandle,
+) -> Result<Vec<T>> {
+    let store = app_handle.store(APP_STATE_FILENAME)?;
+    parse_catalog(store.get("servers"))
 }
 
-pub async fn get_client_server_config() -> HashMap<String, ClientServerConfig> {
-    debug!("get_client_server_config core");
-    let config = ClientConfig::load();
-    let mut id_config_map = HashMap::new();
-    config
-        .mcp_servers
+/// Reads the catalog from the `servers` value of MCPHub's state, a JSON string.
+pub fn parse_catalog<T: for<'de> Deserialize<'de>>(raw_servers: Option<Value>) -> Result<Vec<T>> {
+    let raw_servers = raw_servers.ok_or(AppError::CatalogMissing)?;
+    let raw_servers_str: String =
+        serde_json::from_value(raw_servers).context("Failed to read the server catalog")?;
+    let servers: Vec<T> =
+        serde_json::from_str(&raw_servers_str).context("Failed to read the server catalog")?;
+    Ok(servers)
+}
+
+pub fn find_catalog_server(catalog: Vec<BackendServer>, catalog_id: &str) -> Result<BackendServer> {
+    catalog
         .into_iter()
-        .for_each(|(title, server_config)| {
-            id_config_map.insert(title, server_config);
-        });
+        .find(|server| server.base.id == catalog_id)
+        .ok_or_else(|| {
+            AppError::ServerNotFound {
+                server_id: catalog_id.to_string(),
+            }
+            .into()
+        })
+}
+
+/// Whether the catalog has a server with id `catalog_id`.
+pub fn is_catalog_server(app_handle: &tauri::AppHandle, catalog_id: &str) -> Result<bool> {
+    Ok(get_servers_from_store::<BackendServer>(app_handle)?
+        .iter()
+        .any(|server| server.base.id == catalog_id))
+}
+
+/// Catalog server `catalog_id`.
+pub fn catalog_server(app_handle: &tauri::AppHandle, catalog_id: &str) -> Result<BackendServer> {
+    find_catalog_server(get_servers_from_store(app_handle)?, catalog_id)
+}
+
+pub async fn get_client_server_config(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+) -> Result<HashMap<String, ClientServerConfig>> {
+    debug!("get_client_server_config core");
+    let id_config_map = read_servers(get_client(app_handle, target)?.as_ref())?;
     debug!("get_client_server_config core: loaded id_env_map");
-    id_config_map
+    Ok(id_config_map)
 }
 
-pub async fn load_all_frontend_servers(app_handle: &tauri::AppHandle) -> Vec<FrontendServer> {
-    let backend_servers = get_servers_from_store::<BackendServer>(app_handle);
+pub async fn load_all_frontend_servers(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+) -> Result<Vec<FrontendServer>> {
+    let backend_servers = get_servers_from_store::<BackendServer>(app_handle)?;
     debug!("load_all_frontend_servers core: loaded servers");
-    let id_config_map = get_client_server_config().await;
+    let id_config_map = get_client_server_config(app_handle, target).await?;
     debug!("load_all_frontend_servers core: loaded id_env_map");
+    let parked_servers = load_parked_servers(app_handle)?;
 
-    backend_servers
+    // Enabled entries from the config, then disabled ones parked by MCPHub.
+    let mut configured: Vec<(&str, &ClientServerConfig, bool)> = id_config_map
+        .iter()
+        .map(|(server_id, server_config)| (server_id.as_str(), server_config, true))
+        .chain(
+            parked_servers
+                .iter()
+                .filter(|parked| {
+                    parked.client_id == target.client_id() && parked.scope == target.scope
+                })
+                .filter(|parked| !id_config_map.contains_key(&parked.server_id))
+                .map(|parked| (parked.server_id.as_str(), &parked.config, false)),
+        )
+        .collect();
+    configured.sort_by(|a, b| a.0.cmp(b.0));
+
+    Ok(backend_servers
         .into_iter()
         .map(|mut backend_server| {
-            let is_installed = id_config_map.contains_key(&backend_server.base.id);
-            let env = if is_installed {
-                id_config_map
-                    .get(&backend_server.base.id)
-                    .unwrap()
-                    .env
-                    .clone()
-            } else {
-                backend_server.command_info.env
-            };
+            let catalog_id = backend_server.base.id.clone();
+            let mut instances: Vec<ServerInstance> = configured
+                .iter()
+                .filter(|(server_id, server_config, _)| {
+                    instance_catalog_id(server_id, server_config) == catalog_id
+                })
+                .map(|(server_id, server_config, enabled)| ServerInstance {
+                    id: server_id.to_string(),
+                    enabled: *enabled,
+                    env: entry_env(server_config),
+                    // Entries may hold the values alone, as Codex entries do.
+                    input_args: backend_server
+                        .input_args(Some(input_arg_values(&server_config.input_args)))
+                        .unwrap_or_else(|_| server_config.input_args.clone()),
+                })
+                .collect();
+            instances.sort_by_key(|instance| instance.id != catalog_id);
 
-            let arg_values = if is_installed {
-                id_config_map
-                    .get(&backend_server.base.id)
-                    .unwrap()
-                    .input_arg
-                    .value
-                    .clone()
-            } else {
-                vec![]
+            let first = instances.first();
+            let is_installed = first.is_some();
+            let enabled = first.is_some_and(|instance| instance.enabled);
+            let env = match first {
+                Some(instance) => instance.env.clone(),
+                None => backend_server.default_env(),
             };
+            let env_schema = std::mem::take(&mut backend_server.command_info.env)
+                .into_iter()
+                .map(|(key, mut schema)| {
+                    schema.secret = Some(schema.is_secret(&key));
+                    (key, schema)
+                })
+                .collect();
 
-            backend_server.command_info.input_arg.value = arg_values;
+            let arg_values = first
+                .map(|instance| input_arg_values(&instance.input_args))
+                .unwrap_or_default();
+            for input_arg in &mut backend_server.command_info.input_args {
+                input_arg.value = arg_values.get(&input_arg.name).cloned().unwrap_or_default();
+            }
 
             FrontendServer {
                 base: backend_server.base,
                 is_installed,
+                enabled,
                 env,
+                env_schema,
                 guide: backend_server.command_info.guide,
                 args: backend_server.command_info.args,
-                input_arg: backend_server.command_info.input_arg,
+                input_args: backend_server.command_info.input_args,
+                instances,
             }
         })
-        .collect()
+        .collect())
+}
+
+/// Env of a config entry as the UI shows it: variables kept in the secret store are
+/// listed with a masked value, which `update_server` takes as "unchanged".
+fn entry_env(server_config: &ClientServerConfig) -> HashMap<String, String> {
+    let mut env = server_config.env.clone();
+    if let Some(launcher) = Launcher::from_entry(server_config) {
+        for key in launcher.spec.secrets.into_keys() {
+            env.insert(key, SECRET_MASK.to_string());
+        }
+    }
+    env
+}
+
+/// Fails when one of `server_ids` is configured outside MCPHub in `servers`, as
+/// MCPHub never replaces entries it doesn't manage.
+pub fn refuse_unmanaged<'a>(
+    servers: &HashMap<String, ClientServerConfig>,
+    server_ids: impl IntoIterator<Item = &'a String>,
+) -> Result<()> {
+    for server_id in server_ids {
+        if servers
+            .get(server_id)
+            .is_some_and(|existing| existing.command_creator != MCPHUB_CREATOR)
+        {
+            bail!(
+                "{} is configured outside MCPHub; rename or adopt it first",
+                server_id
+            );
+        }
+    }
+    Ok(())
+}
+
+/// The catalog server a config entry belongs to.
+pub fn instance_catalog_id<'a>(
+    server_id: &'a str,
+    server_config: &'a ClientServerConfig,
+) -> &'a str {
+    server_config.catalog_id.as_deref().unwrap_or(server_id)
+}
+
+/// Config key for an instance of a catalog server: the catalog id itself for the
+/// default instance, `<catalog id>-<name>` for named ones.
+pub fn instance_id(catalog_id: &str, instance_name: Option<&str>) -> Result<String> {
+    let Some(instance_name) = instance_name.filter(|name| !name.trim().is_empty()) else {
+        return Ok(catalog_id.to_string());
+    };
+    let slug = instance_name
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        bail!(
+            "Instance name `{}` has no ASCII letters or digits",
+            instance_name
+        );
+    }
+    Ok(format!("{}-{}", catalog_id, slug))
+}
+
+/// Fails when a new named instance would take the id of a server already configured,
+/// such as an instance whose name differs only in case or punctuation.
+fn check_instance_free(
+    servers: &HashMap<String, ClientServerConfig>,
+    instance_id: &str,
+) -> Result<()> {
+    if servers.contains_key(instance_id) {
+        bail!(
+            "{} is already configured; choose another instance name",
+            instance_id
+        );
+    }
+    Ok(())
 }
 
 pub async fn load_all_installed_frontend_servers(
     app_handle: &tauri::AppHandle,
-) -> Vec<FrontendServer> {
-    let servers = load_all_frontend_servers(app_handle).await;
-    servers
+    target: &ClientTarget,
+) -> Result<Vec<FrontendServer>> {
+    let servers = load_all_frontend_servers(app_handle, target).await?;
+    Ok(servers
         .into_iter()
         .filter(|server| server.is_installed)
-        .collect()
+        .collect())
 }
 
+/// Installs catalog server `server_id`. With an `instance_name`, a further instance is
+/// added next to any existing ones instead of replacing the default instance.
 pub async fn install_server_function(
     app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
     server_id: &str,
+    instance_name: Option<&str>,
     env: Option<HashMap<String, String>>,
-    input_arg: Option<Vec<String>>,
-) -> bool {
-    let mut servers = get_servers_from_store::<BackendServer>(app_handle);
-    let server = servers
-        .iter_mut()
-        .find(|server| server.base.id == server_id)
-        .unwrap();
-    let mut command = server.command_info.command.clone();
-    let mut arg_configs = server.command_info.args.join(" ");
-    let mut input_arg_config = server.command_info.input_arg.clone();
-    let env = env.unwrap_or_else(|| server.command_info.env.clone());
-    if input_arg.is_some() {
-        input_arg_config.value = input_arg.unwrap();
-        arg_configs = format!("{} {}", arg_configs, escape(Cow::from(input_arg_config.value.join(" "))));
-    }
-
-    let mut config = ClientConfig::load();
-    let store = app_handle.store(APP_STATE_FILENAME).unwrap();
-    let mut args = vec![];
-
-    if command == "npx" {
-        let use_system_node = store
-            .get("use_system_node")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false);
-        let node_path = store
-            .get("node_path")
-            .and_then(|s| s.as_str().map(String::from))
-            .unwrap_or("".to_owned());
-        if !use_system_node {
-            #[cfg(target_os = "macos")]
-            {
-                command = "sh".to_string();
-                args = vec![
-                    "-c".to_string(),
-                    format!("PATH=\"{}:$PATH\" npx {}", node_path, arg_configs),
-                ];
+    input_args: Option<BTreeMap<String, Vec<String>>>,
+) -> Result<ConfigChangeResult> {
+    let _guard = lock_mutations(app_handle);
+    let instance_id = instance_id(server_id, instance_name)?;
+    if instance_id != server_id {
+        let client = get_client(app_handle, target)?;
+        check_instance_free(&read_servers(client.as_ref())?, &instance_id)?;
+    }
+    let source = ChangeSource {
+        operation: Operation::Install,
+        server_id: Some(&instance_id),
+        trigger: "install_server",
+    };
+    write_server_entry(
+        app_handle,
+        target,
+        server_id,
+        &instance_id,
+        env,
+        input_args,
+        source,
+    )
+}
+
+/// Builds the entry for catalog server `catalog_id` and writes it into the target's
+/// config under `instance_id`, replacing any entry of that id.
+fn write_server_entry(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+    catalog_id: &str,
+    instance_id: &str,
+    env: Option<HashMap<String, String>>,
+    input_args: Option<BTreeMap<String, Vec<String>>>,
+    source: ChangeSource,
+) -> Result<ConfigChangeResult> {
+    let entry = build_server_entry(app_handle, target, catalog_id, instance_id, env, input_args)?;
+    let mut saved = SavedEntries::default();
+    let written = entry.save(&mut saved).and_then(|()| {
+        apply_change(app_handle, target, source, |servers| {
+            servers.insert(instance_id.to_string(), entry.config);
+            Ok(())
+        })
+    });
+    saved.finish(written)?;
+    unpark_server(app_handle, target, instance_id)?;
+    ConfigChangeResult::for_target(app_handle, target)
+}
+
+/// A client config entry ready to be written, with the launcher it runs and the
+/// secret values that launcher expects to find in the secret store.
+pub struct ServerEntry {
+    pub config: ClientServerConfig,
+    launcher: Option<Launcher>,
+    /// Secret env values by secret store account.
+    secrets: Vec<(String, String)>,
+}
+
+impl ServerEntry {
+    /// Stores the secret values and writes the launcher, noting in `saved` what they
+    /// replace. Done before the entry is written, so that a client never starts a
+    /// launcher that isn't ready; [`SavedEntries::finish`] puts the old ones back if
+    /// writing the entry fails.
+    pub fn save(&self, saved: &mut SavedEntries) -> Result<()> {
+        for (account, value) in &self.secrets {
+            saved.secrets.push((account.clone(), get_secret(account)?));
+            set_secret(account, value)?;
+        }
+        let Some(launcher) = &self.launcher else {
+            return Ok(());
+        };
+        let previous = Launcher::load(launcher.dir()).ok();
+        if let Some(previous) = &previous {
+            saved.stale.extend(
+                previous
+                    .spec
+                    .secrets
+                    .values()
+                    .filter(|account| !launcher.spec.secrets.values().any(|a| a == *account))
+                    .cloned(),
+            );
+        }
+        saved.launchers.push((launcher.clone(), previous));
+        launcher.save()
+    }
+}
+
+/// What saving entries replaced, kept until the config write they belong to is done.
+#[derive(Default)]
+pub struct SavedEntries {
+    /// Accounts written, with the values they held before.
+    secrets: Vec<(String, Option<String>)>,
+    /// Launchers written, with the ones they replaced.
+    launchers: Vec<(Launcher, Option<Launcher>)>,
+    /// Accounts the replaced launchers read and the new ones don't.
+    stale: Vec<String>,
+}
+
+impl SavedEntries {
+    /// Completes the save once the config write is done: deletes the secrets no
+    /// launcher reads anymore if `written` succeeded, and otherwise puts back what was
+    /// replaced, so the entries still in the config keep running as before.
+    pub fn finish<T>(self, written: Result<T>) -> Result<T> {
+        match written {
+            Ok(value) => {
+                for account in &self.stale {
+                    delete_secret(account)?;
+                }
+                Ok(value)
             }
-            #[cfg(target_os = "windows")]
-            {
-                command = "cmd".to_string();
-                args = vec![
-                    "/c".to_string(),
-                    format!("set PATH=%PATH%;{} && npx {}", node_path, arg_configs),
-                ];
+            Err(e) => {
+                if let Err(restore_error) = self.restore() {
+                    warn!(
+                        "Failed to restore launchers and secrets: {:#}",
+                        restore_error
+                    );
+                }
+                Err(e)
             }
         }
-    } else if command == "uvx" {
-        let use_system_uv = store
-            .get("use_system_uv")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false);
-        let uv_path = store
-            .get("uv_path")
-            .and_then(|s| s.as_str().map(String::from))
-            .unwrap_or("".to_owned());
-        if !use_system_uv {
-            #[cfg(target_os = "macos")]
-            {
-                command = "sh".to_string();
-                args = vec![
-                    "-c".to_string(),
-                    format!("PATH=\"{}:$PATH\" uvx {}", uv_path, arg_configs),
-                ];
+    }
+
+    fn restore(self) -> Result<()> {
+        // Newest first, so an account written twice ends up with its original value.
+        for (account, value) in self.secrets.into_iter().rev() {
+            match value {
+                Some(value) => set_secret(&account, &value)?,
+                None => delete_secret(&account)?,
             }
-            #[cfg(target_os = "windows")]
-            {
-                command = "cmd".to_string();
-                args = vec![
-                    "/c".to_string(),
-                    format!("set PATH=%PATH%;{} && uvx {}", uv_path, arg_configs),
-                ];
+        }
+        for (launcher, previous) in self.launchers.into_iter().rev() {
+            match previous {
+                Some(previous) => previous.save()?,
+                None => launcher.remove()?,
             }
         }
+        Ok(())
     }
+}
 
-    config.mcp_servers.insert(
-        server_id.to_string(),
-        ClientServerConfig {
-            command,
-            args,
-            env,
-            command_creator: "MCPHub".to_string(),
-            input_arg: input_arg_config,
-        },
+/// Where servers get Node.js and uv from, as chosen on the welcome page.
+#[derive(Debug, Default)]
+pub struct RuntimeSettings {
+    node_path: String,
+    use_system_node: bool,
+    uv_path: String,
+    use_system_uv: bool,
+}
+
+impl RuntimeSettings {
+    pub fn load(app_handle: &tauri::AppHandle) -> Result<Self> {
+        let store = app_handle.store(APP_STATE_FILENAME)?;
+        Ok(RuntimeSettings::from_lookup(|key| store.get(key)))
+    }
+
+    /// Reads the settings through `get`, which looks up a key of MCPHub's state.
+    pub fn from_lookup(get: impl Fn(&str) -> Option<Value>) -> Self {
+        let string = |key| {
+            get(key)
+                .and_then(|s| s.as_str().map(String::from))
+                .unwrap_or("".to_owned())
+        };
+        let flag = |key| get(key).and_then(|v| v.as_bool()).unwrap_or(false);
+        RuntimeSettings {
+            node_path: string("node_path"),
+            use_system_node: flag("use_system_node"),
+            uv_path: string("uv_path"),
+            use_system_uv: flag("use_system_uv"),
+        }
+    }
+
+    /// Directory with the runtime of `command` that goes in front of PATH, or `None`
+    /// when the command is taken from the system as is. Fails when the runtime is
+    /// neither installed by MCPHub nor taken from the system.
+    pub fn runtime_dir(&self, command: &str) -> Result<Option<&str>> {
+        let (runtime, use_system, path) = match command {
+            "npx" => ("Node.js", self.use_system_node, &self.node_path),
+            "uvx" => ("uv", self.use_system_uv, &self.uv_path),
+            _ => return Ok(None),
+        };
+        if use_system {
+            return Ok(None);
+        }
+        if path.is_empty() {
+            return Err(AppError::RuntimeMissing {
+                runtime: runtime.to_string(),
+            }
+            .into());
+        }
+        Ok(Some(path))
+    }
+}
+
+/// The client config entry that runs catalog server `catalog_id` for `target`.
+pub fn build_server_entry(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+    catalog_id: &str,
+    instance_id: &str,
+    env: Option<HashMap<String, String>>,
+    input_args: Option<BTreeMap<String, Vec<String>>>,
+) -> Result<ServerEntry> {
+    build_entry(
+        app_handle,
+        target,
+        catalog_id,
+        instance_id,
+        env,
+        input_args,
+        true,
+    )
+}
+
+/// Rebuilds an MCPHub entry put back from a profile, a backup or the journal for
+/// `target`, and saves its launcher and secrets into `saved`. The launcher the entry
+/// ran may have been removed or changed since, or belong to another client. Returns
+/// the config to write, which is `server_config` itself for other entries.
+pub fn rebuild_server_entry(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+    server_id: &str,
+    server_config: &ClientServerConfig,
+    saved: &mut SavedEntries,
+) -> Result<ClientServerConfig> {
+    let catalog_id = instance_catalog_id(server_id, server_config);
+    if server_config.command_creator != MCPHUB_CREATOR
+        || !is_catalog_server(app_handle, catalog_id)?
+    {
+        return Ok(server_config.clone());
+    }
+    // References to secrets in shared files aren't values; leave those to the user.
+    let mut env: HashMap<String, String> = server_config
+        .env
+        .iter()
+        .filter(|(_, value)| !(value.starts_with("${") && value.ends_with('}')))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    if let Some(launcher) = Launcher::from_entry(server_config) {
+        for (key, account) in &launcher.spec.secrets {
+            if let Some(value) = get_secret(account)? {
+                env.insert(key.clone(), value);
+            }
+        }
+    }
+    // Secrets removed along with the launcher can't be recovered, so they may be
+    // missing; the server is restored without them rather than not at all.
+    let entry = build_entry(
+        app_handle,
+        target,
+        catalog_id,
+        server_id,
+        Some(env),
+        Some(input_arg_values(&server_config.input_args)),
+        false,
+    )?;
+    entry.save(saved)?;
+    Ok(entry.config)
+}
+
+/// Secret env variables of the MCPHub entry `server_config` whose values can no longer
+/// be recovered, such as after uninstalling the server removed them.
+pub fn lost_secrets(
+    app_handle: &tauri::AppHandle,
+    server_id: &str,
+    server_config: &ClientServerConfig,
+) -> Result<Vec<String>> {
+    let catalog_id = instance_catalog_id(server_id, server_config);
+    if server_config.command_creator != MCPHUB_CREATOR
+        || !is_catalog_server(app_handle, catalog_id)?
+    {
+        return Ok(vec![]);
+    }
+    catalog_server(app_handle, catalog_id)?.lost_secrets(server_config)
+}
+
+fn build_entry(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+    catalog_id: &str,
+    instance_id: &str,
+    env: Option<HashMap<String, String>>,
+    input_args: Option<BTreeMap<String, Vec<String>>>,
+    validate: bool,
+) -> Result<ServerEntry> {
+    let server = catalog_server(app_handle, catalog_id)?;
+    let input_args = server.input_args(input_args)?;
+    // Files checked into a repository are used by teammates too, so they must not
+    // point at this machine's launchers, runtimes or secret store.
+    let shared = target.scope.is_shared();
+    let client = get_client(app_handle, target)?;
+    let config_path = client.config_path()?;
+    let launcher = match shared {
+        true => None,
+        false => {
+            let spec = LaunchSpec {
+                app_state: app_state_path(app_handle)?,
+                catalog_id: catalog_id.to_string(),
+                input_args: input_arg_values(&input_args),
+                secrets: BTreeMap::new(),
+            };
+            Some(Launcher::new(app_handle, &config_path, instance_id, spec)?)
+        }
+    };
+    let runtime = launcher
+        .is_some()
+        .then(|| RuntimeSettings::load(app_handle))
+        .transpose()?;
+    let argv = server.argv(&input_args, runtime.as_ref())?;
+    // Without env the catalog's defaults apply, and values it requires are filled in
+    // through the config dialog once the server is installed.
+    let validate = validate && env.is_some();
+    let mut server_config = ClientServerConfig {
+        command: argv.program,
+        args: argv.args,
+        env: env.unwrap_or_else(|| server.default_env()),
+        command_creator: MCPHUB_CREATOR.to_string(),
+        input_args,
+        catalog_id: (instance_id != catalog_id).then(|| catalog_id.to_string()),
+        url: None,
+        other_fields: HashMap::new(),
+    };
+
+    let Some(launcher) = launcher else {
+        // Shared files only reference secrets, so their values may be left out.
+        if validate {
+            server.validate_env(&server_config.env, false)?;
+        }
+        // Secrets stay out of shared files; each user provides their own.
+        for (key, value) in server_config.env.iter_mut() {
+            if server.is_secret_env(key) {
+                *value = client.secret_reference(instance_id, key);
+            }
+        }
+        return Ok(ServerEntry {
+            config: server_config,
+            launcher: None,
+            secrets: vec![],
+        });
+    };
+    launcher_entry(
+        &server,
+        &config_path,
+        instance_id,
+        server_config,
+        launcher,
+        validate,
+    )
+}
+
+/// Completes the entry of instance `instance_id` that runs `launcher`: secret env
+/// values move to the secret store, and the entry is pointed at the launcher.
+fn launcher_entry(
+    server: &BackendServer,
+    config_path: &Path,
+    instance_id: &str,
+    mut server_config: ClientServerConfig,
+    mut launcher: Launcher,
+    validate: bool,
+) -> Result<ServerEntry> {
+    keep_stored_secrets(&mut server_config.env, &launcher);
+    if validate {
+        server.validate_env(&server_config.env, true)?;
+    }
+    let secrets = move_secrets(
+        config_path,
+        instance_id,
+        server,
+        &mut server_config,
+        &mut launcher,
     );
-    config.save();
-    true
+    launcher.write_to(&mut server_config);
+    Ok(ServerEntry {
+        config: server_config,
+        launcher: Some(launcher),
+        secrets,
+    })
 }
 
-pub async fn uninstall_server_function(server_id: &str) -> bool {
-    let mut config = ClientConfig::load();
-    config.mcp_servers.remove(&server_id.to_string());
-    config.save();
-    true
+/// Adds the secrets the current launcher of the instance reads and `env` leaves out,
+/// masked so that their stored values still apply. Callers may send only the variables
+/// they changed, and a secret must not be deleted just because it wasn't among them.
+fn keep_stored_secrets(env: &mut HashMap<String, String>, launcher: &Launcher) {
+    let Ok(previous) = Launcher::load(launcher.dir()) else {
+        return;
+    };
+    for key in previous.spec.secrets.into_keys() {
+        env.entry(key).or_insert_with(|| SECRET_MASK.to_string());
+    }
 }
 
+/// Moves the secret env variables of `server_config` to the launcher, which reads them
+/// from the secret store at start. Returns the values to store by account; masked
+/// values are left out, as the stored ones still apply.
+fn move_secrets(
+    config_path: &Path,
+    instance_id: &str,
+    server: &BackendServer,
+    server_config: &mut ClientServerConfig,
+    launcher: &mut Launcher,
+) -> Vec<(String, String)> {
+    // A masked value stands for one already in the secret store, secret or not.
+    let keys: Vec<String> = server_config
+        .env
+        .iter()
+        .filter(|(key, value)| server.is_secret_env(key) || *value == SECRET_MASK)
+        .map(|(key, _)| key.clone())
+        .collect();
+    let mut values = vec![];
+    for key in keys {
+        let value = server_config.env.remove(&key).unwrap_or_default();
+        let account = secret_account(config_path, instance_id, &key);
+        if value != SECRET_MASK {
+            values.push((account.clone(), value));
+        }
+        launcher.spec.secrets.insert(key, account);
+    }
+    values
+}
+
+/// Removes the launcher an entry runs, along with the secrets it reads.
+fn remove_entry_launcher(server_config: &ClientServerConfig) -> Result<()> {
+    if let Some(launcher) = Launcher::from_entry(server_config) {
+        for account in launcher.spec.secrets.values() {
+            delete_secret(account)?;
+        }
+        launcher.remove()?;
+    }
+    Ok(())
+}
+
+pub async fn uninstall_server_function(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+    server_id: &str,
+) -> Result<ConfigChangeResult> {
+    let _guard = lock_mutations(app_handle);
+    let source = ChangeSource {
+        operation: Operation::Uninstall,
+        server_id: Some(server_id),
+        trigger: "uninstall_server",
+    };
+    let mut removed = None;
+    apply_change(app_handle, target, source, |servers| {
+        removed = servers.remove(server_id);
+        Ok(())
+    })?;
+    let parked = unpark_server(app_handle, target, server_id)?;
+    for server_config in removed.iter().chain(parked.iter()) {
+        remove_entry_launcher(server_config)?;
+    }
+    ConfigChangeResult::for_target(app_handle, target)
+}
+
+/// Rewrites the instance `server_id` with new env and input argument values.
 pub async fn update_server_function(
     app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+    server_id: &str,
+    env: Option<HashMap<String, String>>,
+    input_args: Option<BTreeMap<String, Vec<String>>>,
+) -> Result<ConfigChangeResult> {
+    let _guard = lock_mutations(app_handle);
+    let (catalog_id, configured) = resolve_instance(app_handle, target, server_id)?;
+    let operation = if configured {
+        Operation::Update
+    } else {
+        Operation::Install
+    };
+    let source = ChangeSource {
+        operation,
+        server_id: Some(server_id),
+        trigger: "update_server",
+    };
+    write_server_entry(
+        app_handle,
+        target,
+        &catalog_id,
+        server_id,
+        env,
+        input_args,
+        source,
+    )
+}
+
+/// Catalog id of the instance `server_id`, and whether the instance is in the config
+/// right now rather than parked or missing.
+fn resolve_instance(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+    server_id: &str,
+) -> Result<(String, bool)> {
+    let servers = read_servers(get_client(app_handle, target)?.as_ref())?;
+    let parked = load_parked_servers(app_handle)?
+        .into_iter()
+        .find(|parked| parked.is_for(target, server_id));
+    let current = servers
+        .get(server_id)
+        .or(parked.as_ref().map(|parked| &parked.config));
+    let catalog_id = current
+        .map(|server_config| instance_catalog_id(server_id, server_config))
+        .unwrap_or(server_id)
+        .to_string();
+    Ok((catalog_id, servers.contains_key(server_id)))
+}
+
+/// What a command that changed a client config reports back.
+#[derive(Debug, Serialize)]
+pub struct ConfigChangeResult {
+    /// The client app is running and only sees the change once restarted.
+    #[serde(rename = "restartRequired")]
+    restart_required: bool,
+}
+
+impl ConfigChangeResult {
+    pub fn for_target(app_handle: &tauri::AppHandle, target: &ClientTarget) -> Result<Self> {
+        let client = get_client(app_handle, target)?;
+        Ok(ConfigChangeResult {
+            restart_required: needs_restart(client.as_ref()),
+        })
+    }
+}
+
+/// What a server command would do to a client config, computed without writing it.
+#[derive(Debug, Serialize)]
+pub struct ChangePreview {
+    #[serde(rename = "serverId")]
+    server_id: String,
+    #[serde(rename = "configPath")]
+    config_path: String,
+    /// The entry exactly as it would be written, except that secret env values are
+    /// masked. `None` when the server would be removed.
+    entry: Option<ClientServerConfig>,
+    /// Differences from the current file, masked the same way.
+    changes: Vec<ServerChange>,
+}
+
+pub async fn preview_install_server_function(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
     server_id: &str,
+    instance_name: Option<&str>,
     env: Option<HashMap<String, String>>,
-    input_arg: Option<Vec<String>>,
-) -> bool {
-    let mut config = ClientConfig::load();
-    if !config.mcp_servers.contains_key(server_id) {
-        install_server_function(&app_handle, server_id, env, input_arg).await;
+    input_args: Option<BTreeMap<String, Vec<String>>>,
+) -> Result<ChangePreview> {
+    let instance_id = instance_id(server_id, instance_name)?;
+    if instance_id != server_id {
+        let client = get_client(app_handle, target)?;
+        check_instance_free(&read_servers(client.as_ref())?, &instance_id)?;
+    }
+    let entry = build_server_entry(app_handle, target, server_id, &instance_id, env, input_args)?;
+    preview_change(app_handle, target, &instance_id, |servers| {
+        servers.insert(instance_id.clone(), entry.config);
+        Ok(())
+    })
+}
+
+pub async fn preview_update_server_function(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+    server_id: &str,
+    env: Option<HashMap<String, String>>,
+    input_args: Option<BTreeMap<String, Vec<String>>>,
+) -> Result<ChangePreview> {
+    let (catalog_id, _) = resolve_instance(app_handle, target, server_id)?;
+    let entry = build_server_entry(app_handle, target, &catalog_id, server_id, env, input_args)?;
+    preview_change(app_handle, target, server_id, |servers| {
+        servers.insert(server_id.to_string(), entry.config);
+        Ok(())
+    })
+}
+
+pub async fn preview_uninstall_server_function(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+    server_id: &str,
+) -> Result<ChangePreview> {
+    preview_change(app_handle, target, server_id, |servers| {
+        servers.remove(server_id);
+        Ok(())
+    })
+}
+
+fn preview_change(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+    server_id: &str,
+    change: impl FnOnce(&mut HashMap<String, ClientServerConfig>) -> Result<()>,
+) -> Result<ChangePreview> {
+    let client = get_client(app_handle, target)?;
+    let servers = preview_servers(client.as_ref(), change)?;
+    let mut changes = diff_servers(&servers.before, &servers.after);
+    mask_changes(app_handle, &mut changes, &servers.before, &servers.after);
+    let mut entry = servers.after.get(server_id).cloned();
+    if let Some(entry) = &mut entry {
+        let is_secret = secret_env_check(app_handle, server_id, Some(entry));
+        mask_env(&mut entry.env, is_secret);
+    }
+    Ok(ChangePreview {
+        server_id: server_id.to_string(),
+        config_path: client.config_path()?.to_string_lossy().to_string(),
+        entry,
+        changes,
+    })
+}
+
+/// Tells which env variables of the entry `server_config` under `server_id` hold
+/// secrets: as the catalog's schema says for catalog servers, judging by the name for
+/// any other entry.
+pub fn secret_env_check(
+    app_handle: &tauri::AppHandle,
+    server_id: &str,
+    server_config: Option<&ClientServerConfig>,
+) -> impl Fn(&str) -> bool {
+    let catalog_id = server_config.map_or(server_id, |server_config| {
+        instance_catalog_id(server_id, server_config)
+    });
+    let server = catalog_server(app_handle, catalog_id).ok();
+    move |key| match &server {
+        Some(server) => server.is_secret_env(key),
+        None => is_secret_key(key),
+    }
+}
+
+/// Masks the secret values in `changes`, which were diffed from `before` to `after`.
+pub fn mask_changes(
+    app_handle: &tauri::AppHandle,
+    changes: &mut [ServerChange],
+    before: &HashMap<String, ClientServerConfig>,
+    after: &HashMap<String, ClientServerConfig>,
+) {
+    for change in changes.iter_mut() {
+        let server_config = after
+            .get(&change.server_id)
+            .or_else(|| before.get(&change.server_id));
+        let is_secret = secret_env_check(app_handle, &change.server_id, server_config);
+        for field in change.fields.iter_mut() {
+            mask_field(field, &is_secret);
+        }
+    }
+}
+
+/// Masks secret values in a change to `/env` or to one of its variables.
+fn mask_field(field: &mut FieldChange, is_secret: impl Fn(&str) -> bool) {
+    let values = [&mut field.before, &mut field.after].into_iter().flatten();
+    if field.path == "/env" {
+        for value in values {
+            if let Value::Object(env) = value {
+                for (key, value) in env.iter_mut() {
+                    if is_secret(key) {
+                        *value = Value::from(SECRET_MASK);
+                    }
+                }
+            }
+        }
+    } else if let Some(key) = field.path.strip_prefix("/env/") {
+        if is_secret(key) {
+            for value in values {
+                *value = Value::from(SECRET_MASK);
+            }
+        }
+    }
+}
+
+/// Store key of the entries taken out of client configs by `disable_server`.
+const PARKED_SERVERS_KEY: &str = "parked_servers";
+
+/// A disabled server's entry, kept exactly as it was in the client config.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct ParkedServer {
+    #[serde(rename = "clientId")]
+    client_id: String,
+    scope: ConfigScope,
+    #[serde(rename = "serverId")]
+    server_id: String,
+    config: ClientServerConfig,
+}
+
+impl ParkedServer {
+    fn is_for(&self, target: &ClientTarget, server_id: &str) -> bool {
+        self.client_id == target.client_id()
+            && self.scope == target.scope
+            && self.server_id == server_id
+    }
+}
+
+fn load_parked_servers(app_handle: &tauri::AppHandle) -> Result<Vec<ParkedServer>> {
+    let store = app_handle.store(APP_STATE_FILENAME)?;
+    match store.get(PARKED_SERVERS_KEY) {
+        Some(parked) => Ok(serde_json::from_value(parked)?),
+        None => Ok(vec![]),
+    }
+}
+
+fn save_parked_servers(app_handle: &tauri::AppHandle, parked: &[ParkedServer]) -> Result<()> {
+    let store = app_handle.store(APP_STATE_FILENAME)?;
+    store.set(PARKED_SERVERS_KEY, serde_json::to_value(parked)?);
+    Ok(())
+}
+
+/// Drops the parked copy of a server, if any, and returns its entry.
+pub fn unpark_server(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+    server_id: &str,
+) -> Result<Option<ClientServerConfig>> {
+    let mut parked = load_parked_servers(app_handle)?;
+    let config = take_parked(&mut parked, target, server_id);
+    if config.is_some() {
+        save_parked_servers(app_handle, &parked)?;
+    }
+    Ok(config)
+}
+
+/// Takes the parked copies of a server out of `parked` and returns the entry.
+fn take_parked(
+    parked: &mut Vec<ParkedServer>,
+    target: &ClientTarget,
+    server_id: &str,
+) -> Option<ClientServerConfig> {
+    let (dropped, kept) = std::mem::take(parked)
+        .into_iter()
+        .partition::<Vec<ParkedServer>, _>(|parked| parked.is_for(target, server_id));
+    *parked = kept;
+    dropped.into_iter().next().map(|parked| parked.config)
+}
+
+/// Adds `config` to `parked` as the entry of `server_id`, replacing any parked before.
+fn park(
+    parked: &mut Vec<ParkedServer>,
+    target: &ClientTarget,
+    server_id: String,
+    config: ClientServerConfig,
+) {
+    parked.retain(|parked| !parked.is_for(target, &server_id));
+    parked.push(ParkedServer {
+        client_id: target.client_id().to_string(),
+        scope: target.scope.clone(),
+        server_id,
+        config,
+    });
+}
+
+/// Puts a parked entry back into `servers`, unless its id has been taken since.
+fn restore_parked(
+    servers: &mut HashMap<String, ClientServerConfig>,
+    server_id: &str,
+    config: ClientServerConfig,
+) -> Result<()> {
+    if servers.contains_key(server_id) {
+        bail!("{} is already configured", server_id);
+    }
+    servers.insert(server_id.to_string(), config);
+    Ok(())
+}
+
+/// Keeps entries taken out of `target`'s config, along with their launchers and
+/// secrets, so that `enable_server_function` can put them back.
+pub fn park_servers(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+    removed: Vec<(String, ClientServerConfig)>,
+) -> Result<()> {
+    let mut parked = load_parked_servers(app_handle)?;
+    for (server_id, config) in removed {
+        park(&mut parked, target, server_id, config);
+    }
+    save_parked_servers(app_handle, &parked)
+}
+
+/// Takes a server out of the client config without losing its env values and input
+/// arguments; `enable_server_function` puts it back exactly as it was.
+pub async fn disable_server_function(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+    server_id: &str,
+) -> Result<ConfigChangeResult> {
+    let _guard = lock_mutations(app_handle);
+    let source = ChangeSource {
+        operation: Operation::Disable,
+        server_id: Some(server_id),
+        trigger: "disable_server",
+    };
+    let mut removed = vec![];
+    apply_change(app_handle, target, source, |servers| {
+        let config = servers
+            .remove(server_id)
+            .ok_or_else(|| AppError::ServerNotInstalled {
+                server_id: server_id.to_string(),
+            })?;
+        removed.push((server_id.to_string(), config));
+        Ok(())
+    })?;
+    park_servers(app_handle, target, removed)?;
+    ConfigChangeResult::for_target(app_handle, target)
+}
+
+pub async fn enable_server_function(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+    server_id: &str,
+) -> Result<ConfigChangeResult> {
+    let _guard = lock_mutations(app_handle);
+    let parked = load_parked_servers(app_handle)?
+        .into_iter()
+        .find(|parked| parked.is_for(target, server_id))
+        .with_context(|| format!("{} is not disabled", server_id))?;
+    let source = ChangeSource {
+        operation: Operation::Enable,
+        server_id: Some(server_id),
+        trigger: "enable_server",
+    };
+    apply_change(app_handle, target, source, |servers| {
+        restore_parked(servers, server_id, parked.config)
+    })?;
+    unpark_server(app_handle, target, server_id)?;
+    ConfigChangeResult::for_target(app_handle, target)
+}
+
+/// A catalog server an unmanaged config entry most likely is.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CatalogMatch {
+    #[serde(rename = "catalogId")]
+    catalog_id: String,
+    title: String,
+    /// Confidence between 0 and 1; an exact package name match scores 1.
+    score: f64,
+}
+
+/// A server entry in a client config that MCPHub didn't write.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UnmanagedServer {
+    /// Key of the entry in the client config.
+    #[serde(rename = "serverId")]
+    server_id: String,
+    command: String,
+    args: Vec<String>,
+    #[serde(rename = "envKeys")]
+    env_keys: Vec<String>,
+    url: Option<String>,
+    #[serde(rename = "catalogMatch")]
+    catalog_match: Option<CatalogMatch>,
+}
+
+/// Matches scoring below this are not offered.
+const MIN_MATCH_SCORE: f64 = 0.6;
+
+pub async fn load_unmanaged_servers(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+) -> Result<Vec<UnmanagedServer>> {
+    let catalog = get_servers_from_store::<BackendServer>(app_handle)?;
+    let id_config_map = get_client_server_config(app_handle, target).await?;
+    let mut unmanaged: Vec<UnmanagedServer> = id_config_map
+        .into_iter()
+        .filter(|(_, server_config)| server_config.command_creator != MCPHUB_CREATOR)
+        .map(|(server_id, server_config)| {
+            let mut env_keys: Vec<String> = server_config.env.keys().cloned().collect();
+            env_keys.sort();
+            UnmanagedServer {
+                catalog_match: match_catalog(&catalog, &server_id, &server_config),
+                server_id,
+                command: server_config.command,
+                args: server_config.args,
+                env_keys,
+                url: server_config.url,
+            }
+        })
+        .collect();
+    unmanaged.sort_by(|a, b| a.server_id.cmp(&b.server_id));
+    Ok(unmanaged)
+}
+
+/// Takes over an entry MCPHub didn't write. Its command, arguments and env stay as they
+/// are; the entry is marked as MCPHub's and, when `catalog_id` is given, moved under
+/// that id so it shows up as the installed catalog server. If the catalog server is
+/// installed already, the entry keeps its key and becomes another instance of it.
+pub async fn adopt_server_function(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+    server_id: &str,
+    catalog_id: Option<String>,
+) -> Result<()> {
+    let _guard = lock_mutations(app_handle);
+    let catalog = get_servers_from_store::<BackendServer>(app_handle)?;
+    let catalog_server = match &catalog_id {
+        Some(catalog_id) => Some(
+            catalog
+                .iter()
+                .find(|server| server.base.id == *catalog_id)
+                .ok_or_else(|| AppError::ServerNotFound {
+                    server_id: catalog_id.to_string(),
+                })?,
+        ),
+        None => None,
+    };
+    let source = ChangeSource {
+        operation: Operation::Adopt,
+        server_id: Some(server_id),
+        trigger: "adopt_server",
+    };
+    apply_change(app_handle, target, source, |servers| {
+        let mut server_config =
+            servers
+                .remove(server_id)
+                .ok_or_else(|| AppError::ServerNotInstalled {
+                    server_id: server_id.to_string(),
+                })?;
+        if server_config.command_creator == MCPHUB_CREATOR {
+            bail!("{} is already managed by MCPHub", server_id);
+        }
+        // Becomes the default instance when that is free, else another instance.
+        let new_id = match catalog_id.as_deref() {
+            Some(catalog_id) if !servers.contains_key(catalog_id) => catalog_id,
+            _ => server_id,
+        };
+        if new_id == server_id && catalog_id.as_deref() != Some(server_id) {
+            server_config.catalog_id = catalog_id.clone();
+        }
+        server_config.command_creator = MCPHUB_CREATOR.to_string();
+        if let Some(catalog_server) = catalog_server {
+            let tokens = trailing_args(&catalog_server.command_info.args, &server_config.args);
+            server_config.input_args =
+                split_input_args(&catalog_server.command_info.input_args, tokens);
+        }
+        servers.insert(new_id.to_string(), server_config);
+        Ok(())
+    })?;
+    Ok(())
+}
+
+/// Arguments following the catalog's own ones, which is where input arguments go.
+fn trailing_args(catalog_args: &[String], args: &[String]) -> Vec<String> {
+    let tokens = arg_tokens(args);
+    let Some(package) = catalog_args.iter().find(|arg| !arg.starts_with('-')) else {
+        return vec![];
+    };
+    let package = package_name(package);
+    match tokens
+        .iter()
+        .position(|token| package_name(token) == package)
+    {
+        Some(position) => tokens[position + 1..].to_vec(),
+        None => vec![],
+    }
+}
+
+/// Gives each input argument its values from `tokens`: flagged arguments take the
+/// values after their flag, and positional ones the remaining tokens in order, with a
+/// multiple one taking all that are left.
+fn split_input_args(input_args: &[InputArg], tokens: Vec<String>) -> Vec<InputArg> {
+    let mut input_args = input_args.to_vec();
+    for input_arg in &mut input_args {
+        input_arg.value.clear();
+    }
+    let mut positional = vec![];
+    let mut tokens = tokens.into_iter();
+    'tokens: while let Some(token) = tokens.next() {
+        for input_arg in &mut input_args {
+            let Some(flag) = input_arg.flag.as_deref() else {
+                continue;
+            };
+            if matches!(input_arg.class, ArgClass::Switch) {
+                if token == flag {
+                    input_arg.value = vec!["true".to_string()];
+                    continue 'tokens;
+                }
+            } else if flag.ends_with('=') {
+                if let Some(value) = token.strip_prefix(flag) {
+                    input_arg.value.push(value.to_string());
+                    continue 'tokens;
+                }
+            } else if token == flag {
+                input_arg.value.extend(tokens.next());
+                continue 'tokens;
+            }
+        }
+        positional.push(token);
+    }
+    let mut positional = positional.into_iter();
+    for input_arg in input_args
+        .iter_mut()
+        .filter(|input_arg| input_arg.flag.is_none())
+    {
+        match input_arg.multiplicity {
+            ArgumentMultiplicity::Single => input_arg.value.extend(positional.next()),
+            ArgumentMultiplicity::Multiple => input_arg.value.extend(positional.by_ref()),
+        }
+    }
+    input_args
+}
+
+fn match_catalog(
+    catalog: &[BackendServer],
+    server_id: &str,
+    server_config: &ClientServerConfig,
+) -> Option<CatalogMatch> {
+    let mut tokens = arg_tokens(&server_config.args);
+    tokens.push(server_config.command.clone());
+    let packages: Vec<String> = tokens
+        .iter()
+        .filter(|token| !token.starts_with('-'))
+        .map(|token| package_name(token))
+        .collect();
+    let entry_name = normalize_name(server_id);
+
+    catalog
+        .iter()
+        .filter_map(|server| {
+            let package = server
+                .command_info
+                .args
+                .iter()
+                .find(|arg| !arg.starts_with('-'))
+                .map(|arg| package_name(arg))
+                .unwrap_or_default();
+            let package_key = normalize_name(&package);
+            let score = if !package.is_empty() && packages.contains(&package) {
+                1.0
+            } else if package_key.len() >= 4
+                && packages.iter().any(|token| token.contains(&package_key))
+            {
+                // A checkout of the package, e.g. `node ~/src/brave-search/index.js`.
+                0.8
+            } else {
+                let by_package = packages
+                    .iter()
+                    .map(|candidate| similarity(&normalize_name(candidate), &package_key))
+                    .fold(0.0, f64::max);
+                let by_name = similarity(&entry_name, &normalize_name(&server.base.id))
+                    .max(similarity(&entry_name, &normalize_name(&server.base.title)));
+                (by_package * 0.9).max(by_name * 0.7)
+            };
+            (score >= MIN_MATCH_SCORE).then(|| CatalogMatch {
+                catalog_id: server.base.id.clone(),
+                title: server.base.title.clone(),
+                score,
+            })
+        })
+        .max_by(|a, b| a.score.total_cmp(&b.score))
+}
+
+/// Splits arguments into words, so packages inside `sh -c '...'` wrappers are seen.
+fn arg_tokens(args: &[String]) -> Vec<String> {
+    args.iter()
+        .flat_map(|arg| arg.split_whitespace())
+        .map(|token| token.trim_matches(|c| c == '\'' || c == '"').to_string())
+        .filter(|token| !token.is_empty())
+        .collect()
+}
+
+/// A package spec without its version, e.g. `@scope/pkg@1.2` becomes `@scope/pkg`.
+fn package_name(spec: &str) -> String {
+    let spec = spec.to_lowercase();
+    let version_at = if let Some(rest) = spec.strip_prefix('@') {
+        rest.find('@').map(|i| i + 1)
     } else {
-        uninstall_server_function(server_id).await;
-        install_server_function(&app_handle, server_id, env, input_arg).await;
+        spec.find('@')
+    };
+    let spec = match version_at {
+        Some(i) => &spec[..i],
+        None => spec.as_str(),
+    };
+    spec.split("==").next().unwrap_or_default().to_string()
+}
+
+/// Reduces a package or server name to the part that identifies it, so
+/// `@modelcontextprotocol/server-github` and `github-mcp` compare as `github`.
+fn normalize_name(name: &str) -> String {
+    let name = name.to_lowercase();
+    let name = name.rsplit('/').next().unwrap_or_default();
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| {
+            !["mcp", "server", "modelcontextprotocol"].contains(word) && !word.is_empty()
+        })
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Normalized edit distance similarity between 0 and 1.
+fn similarity(a: &str, b: &str) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == cb {
+                previous
+            } else {
+                1 + previous.min(row[j]).min(current)
+            };
+            previous = current;
+        }
+    }
+    1.0 - row[b.len()] as f64 / a.len().max(b.len()) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn backend_server(command_info: Value) -> BackendServer {
+        serde_json::from_value(json!({
+            "id": "demo",
+            "title": "Demo",
+            "description": "",
+            "creator": "",
+            "tags": [],
+            "logoUrl": "",
+            "rating": 5,
+            "publishDate": "",
+            "commandInfo": command_info,
+        }))
+        .unwrap()
+    }
+
+    fn env(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    /// Builds the entry of the instance `demo` of a config at `config_path`, with its
+    /// launcher in `dir`, and saves the launcher and secrets.
+    fn save_entry(
+        server: &BackendServer,
+        config_path: &Path,
+        dir: &Path,
+        env: HashMap<String, String>,
+    ) -> (ServerEntry, SavedEntries) {
+        let spec = LaunchSpec {
+            app_state: dir.join(APP_STATE_FILENAME),
+            catalog_id: "demo".to_string(),
+            input_args: BTreeMap::new(),
+            secrets: BTreeMap::new(),
+        };
+        let server_config = ClientServerConfig {
+            command: "npx".to_string(),
+            args: vec![],
+            env,
+            command_creator: MCPHUB_CREATOR.to_string(),
+            input_args: vec![],
+            catalog_id: None,
+            url: None,
+            other_fields: HashMap::new(),
+        };
+        let launcher = Launcher::in_dir(dir, spec);
+        let entry =
+            launcher_entry(server, config_path, "demo", server_config, launcher, true).unwrap();
+        let mut saved = SavedEntries::default();
+        entry.save(&mut saved).unwrap();
+        (entry, saved)
+    }
+
+    #[test]
+    fn secrets_move_to_the_secret_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("config.json");
+        let server = backend_server(json!({
+            "command": "npx",
+            "args": [],
+            "env": {
+                "DATABASE_URL": { "secret": true },
+                "API_TOKEN_HINT": { "secret": false },
+                "GITHUB_TOKEN": "",
+            },
+        }));
+        let values = env(&[
+            ("DATABASE_URL", "postgres://u:p@db"),
+            ("API_TOKEN_HINT", "starts with ghp_"),
+            ("GITHUB_TOKEN", "ghp_1"),
+        ]);
+        let (entry, saved) = save_entry(&server, &config_path, dir.path(), values);
+        saved.finish(Ok(())).unwrap();
+
+        assert_eq!(
+            entry.config.env,
+            env(&[("API_TOKEN_HINT", "starts with ghp_")])
+        );
+        let secrets = &entry.launcher.as_ref().unwrap().spec.secrets;
+        assert_eq!(
+            secrets.keys().collect::<Vec<_>>(),
+            ["DATABASE_URL", "GITHUB_TOKEN"]
+        );
+        assert_eq!(
+            get_secret(&secrets["DATABASE_URL"]).unwrap().as_deref(),
+            Some("postgres://u:p@db")
+        );
+        let saved = Launcher::load(dir.path()).unwrap();
+        assert_eq!(saved.spec.secrets, *secrets);
+    }
+
+    #[test]
+    fn secrets_removed_with_the_launcher_count_as_lost() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("config.json");
+        let server = backend_server(json!({
+            "command": "npx",
+            "args": [],
+            "env": {
+                "API_KEY": { "secret": true },
+                "LOG_LEVEL": "info",
+            },
+        }));
+        let values = env(&[("API_KEY", "sk-1"), ("LOG_LEVEL", "debug")]);
+        let (entry, saved) = save_entry(&server, &config_path, dir.path(), values);
+        saved.finish(Ok(())).unwrap();
+        let server_config = entry.config.clone();
+        assert!(server.lost_secrets(&server_config).unwrap().is_empty());
+
+        let account = &entry.launcher.as_ref().unwrap().spec.secrets["API_KEY"];
+        delete_secret(account).unwrap();
+        assert_eq!(server.lost_secrets(&server_config).unwrap(), ["API_KEY"]);
+
+        set_secret(account, "sk-1").unwrap();
+        remove_entry_launcher(&server_config).unwrap();
+        assert_eq!(get_secret(account).unwrap(), None);
+        assert_eq!(server.lost_secrets(&server_config).unwrap(), ["API_KEY"]);
+    }
+
+    #[test]
+    fn secrets_left_out_of_an_update_keep_their_values() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("config.json");
+        let server = backend_server(json!({
+            "command": "npx",
+            "args": [],
+            "env": {
+                "API_KEY": { "required": true },
+                "DB_PASSWORD": "",
+                "LOG_LEVEL": "info",
+            },
+        }));
+        let values = env(&[
+            ("API_KEY", "key-1"),
+            ("DB_PASSWORD", "pw-1"),
+            ("LOG_LEVEL", "info"),
+        ]);
+        let (_, saved) = save_entry(&server, &config_path, dir.path(), values);
+        saved.finish(Ok(())).unwrap();
+
+        // Only the field the user edited, as the config dialog used to send.
+        let (entry, saved) = save_entry(
+            &server,
+            &config_path,
+            dir.path(),
+            env(&[("LOG_LEVEL", "debug")]),
+        );
+        assert!(saved.stale.is_empty());
+        saved.finish(Ok(())).unwrap();
+        assert_eq!(entry.config.env, env(&[("LOG_LEVEL", "debug")]));
+        let secrets = &entry.launcher.as_ref().unwrap().spec.secrets;
+        assert_eq!(
+            get_secret(&secrets["API_KEY"]).unwrap().as_deref(),
+            Some("key-1")
+        );
+        assert_eq!(
+            get_secret(&secrets["DB_PASSWORD"]).unwrap().as_deref(),
+            Some("pw-1")
+        );
+
+        // Masked values, as the dialog sends them now, are left alone too.
+        let values = env(&[
+            ("API_KEY", "key-2"),
+            ("DB_PASSWORD", SECRET_MASK),
+            ("LOG_LEVEL", "debug"),
+        ]);
+        let (entry, saved) = save_entry(&server, &config_path, dir.path(), values);
+        assert!(saved.stale.is_empty());
+        saved.finish(Ok(())).unwrap();
+        let secrets = &entry.launcher.as_ref().unwrap().spec.secrets;
+        assert_eq!(
+            get_secret(&secrets["API_KEY"]).unwrap().as_deref(),
+            Some("key-2")
+        );
+        assert_eq!(
+            get_secret(&secrets["DB_PASSWORD"]).unwrap().as_deref(),
+            Some("pw-1")
+        );
+    }
+
+    #[test]
+    fn failed_config_writes_restore_the_previous_launcher() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("config.json");
+        let server = backend_server(json!({
+            "command": "npx",
+            "args": [],
+            "env": { "API_KEY": "", "LOG_LEVEL": "info" },
+        }));
+        let values = env(&[("API_KEY", "key-1"), ("LOG_LEVEL", "info")]);
+        let (before, saved) = save_entry(&server, &config_path, dir.path(), values);
+        saved.finish(Ok(())).unwrap();
+        let before = before.launcher.unwrap().spec.secrets;
+
+        // Replaces API_KEY, then the config write fails.
+        let values = env(&[("API_KEY", "key-2"), ("LOG_LEVEL", "debug")]);
+        let (_, saved) = save_entry(&server, &config_path, dir.path(), values);
+        assert_eq!(
+            get_secret(&before["API_KEY"]).unwrap().as_deref(),
+            Some("key-2")
+        );
+        let conflict: Result<()> = Err(anyhow::anyhow!("config changed on disk"));
+        assert!(saved.finish(conflict).is_err());
+
+        let restored = Launcher::load(dir.path()).unwrap();
+        assert_eq!(restored.spec.secrets, before);
+        assert_eq!(
+            get_secret(&before["API_KEY"]).unwrap().as_deref(),
+            Some("key-1")
+        );
+    }
+
+    #[test]
+    fn failed_config_writes_remove_new_launchers() {
+        let dir = tempfile::tempdir().unwrap();
+        let launcher_dir = dir.path().join("demo");
+        let config_path = dir.path().join("config.json");
+        let server = backend_server(json!({
+            "command": "npx",
+            "args": [],
+            "env": { "API_KEY": "" },
+        }));
+        let values = env(&[("API_KEY", "key-1")]);
+        let (entry, saved) = save_entry(&server, &config_path, &launcher_dir, values);
+        assert!(launcher_dir.exists());
+        let conflict: Result<()> = Err(anyhow::anyhow!("config changed on disk"));
+        assert!(saved.finish(conflict).is_err());
+
+        assert!(!launcher_dir.exists());
+        let account = &entry.launcher.unwrap().spec.secrets["API_KEY"];
+        assert_eq!(get_secret(account).unwrap(), None);
+    }
+
+    #[test]
+    fn previews_mask_the_secrets_the_schema_marks() {
+        let server = backend_server(json!({
+            "command": "npx",
+            "args": [],
+            "env": {
+                "DATABASE_URL": { "secret": true },
+                "API_TOKEN_HINT": { "secret": false },
+            },
+        }));
+        let is_secret = |key: &str| server.is_secret_env(key);
+        let mut field = FieldChange {
+            path: "/env".to_string(),
+            before: None,
+            after: Some(json!({
+                "DATABASE_URL": "postgres://u:p@db",
+                "API_TOKEN_HINT": "starts with ghp_",
+                "SLACK_TOKEN": "xoxb-1",
+            })),
+        };
+        mask_field(&mut field, is_secret);
+        assert_eq!(
+            field.after,
+            Some(json!({
+                "DATABASE_URL": SECRET_MASK,
+                "API_TOKEN_HINT": "starts with ghp_",
+                "SLACK_TOKEN": SECRET_MASK,
+            }))
+        );
+
+        let mut field = FieldChange {
+            path: "/env/DATABASE_URL".to_string(),
+            before: Some(json!("postgres://u:p@old")),
+            after: Some(json!("postgres://u:p@db")),
+        };
+        mask_field(&mut field, is_secret);
+        assert_eq!(field.before, Some(json!(SECRET_MASK)));
+        assert_eq!(field.after, Some(json!(SECRET_MASK)));
+
+        let mut values = env(&[("DATABASE_URL", "postgres://u:p@db"), ("LOG_LEVEL", "info")]);
+        mask_env(&mut values, is_secret);
+        assert_eq!(
+            values,
+            env(&[("DATABASE_URL", SECRET_MASK), ("LOG_LEVEL", "info")])
+        );
+    }
+
+    #[test]
+    fn env_values_are_checked_against_the_catalog_schema() {
+        let server = backend_server(json!({
+            "command": "npx",
+            "args": [],
+            "env": {
+                "API_KEY": { "required": true, "pattern": "sk-[a-z0-9]+" },
+                "REGION": { "pattern": "(" },
+                "LOG_LEVEL": "info",
+            },
+        }));
+        let fields = |result: Result<()>| match result.unwrap_err().downcast::<AppError>() {
+            Ok(AppError::EnvInvalid { fields, .. }) => fields
+                .into_iter()
+                .map(|field| field.key)
+                .collect::<Vec<_>>(),
+            other => panic!("expected envInvalid, got {:?}", other),
+        };
+
+        server
+            .validate_env(&env(&[("API_KEY", "sk-abc1")]), true)
+            .unwrap();
+        assert_eq!(
+            fields(server.validate_env(&env(&[("API_KEY", "xsk-abc1")]), true)),
+            ["API_KEY"]
+        );
+        assert_eq!(fields(server.validate_env(&env(&[]), true)), ["API_KEY"]);
+        // Secrets of shared files are only referenced, so they may be missing.
+        server.validate_env(&env(&[]), false).unwrap();
+
+        server.validate_env_value("API_KEY", "sk-new2").unwrap();
+        assert_eq!(
+            fields(server.validate_env_value("API_KEY", "new")),
+            ["API_KEY"]
+        );
+        server.validate_env_value("LOG_LEVEL", "").unwrap();
+        let error = server.validate_env_value("REGION", "eu").unwrap_err();
+        assert!(format!("{:#}", error).contains("The catalog pattern of REGION is invalid"));
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    fn catalog_entry(id: &str, title: &str, args: &[&str]) -> BackendServer {
+        serde_json::from_value(json!({
+            "id": id,
+            "title": title,
+            "description": "",
+            "creator": "",
+            "tags": [],
+            "logoUrl": "",
+            "rating": 5,
+            "publishDate": "",
+            "commandInfo": { "command": "npx", "args": args },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn unmanaged_entries_match_catalog_servers() {
+        let catalog = [
+            catalog_entry(
+                "brave-search",
+                "Brave Search",
+                &["-y", "@modelcontextprotocol/server-brave-search"],
+            ),
+            catalog_entry(
+                "github",
+                "GitHub",
+                &["-y", "@modelcontextprotocol/server-github"],
+            ),
+            catalog_entry(
+                "postgres",
+                "PostgreSQL",
+                &["-y", "@modelcontextprotocol/server-postgres"],
+            ),
+        ];
+        // Entry id, command and arguments, and the catalog id and score they match.
+        type Case<'a> = (&'a str, &'a str, &'a [&'a str], Option<(&'a str, f64)>);
+        let cases: [Case; 8] = [
+            // The package, with a version.
+            (
+                "search",
+                "npx",
+                &["-y", "@modelcontextprotocol/server-brave-search@0.6.2"],
+                Some(("brave-search", 1.0)),
+            ),
+            // The package inside a shell wrapper.
+            (
+                "gh",
+                "sh",
+                &["-c", "npx -y '@modelcontextprotocol/server-github'"],
+                Some(("github", 1.0)),
+            ),
+            // A checkout of the package.
+            (
+                "search",
+                "node",
+                &["/home/me/src/brave-search/dist/index.js"],
+                Some(("brave-search", 0.8)),
+            ),
+            // A near miss of the package name.
+            (
+                "db",
+                "npx",
+                &["-y", "@acme/server-postgress"],
+                Some(("postgres", 0.8)),
+            ),
+            // Nothing but the entry's name to go by.
+            (
+                "GitHub",
+                "docker",
+                &["run", "-i", "ghcr.io/acme/gh"],
+                Some(("github", 0.7)),
+            ),
+            (
+                "github-mcp",
+                "docker",
+                &["run", "-i", "ghcr.io/acme/gh"],
+                Some(("github", 0.7)),
+            ),
+            // Names that are merely close, and servers the catalog doesn't have.
+            ("gitlab", "docker", &["run", "-i", "ghcr.io/acme/gl"], None),
+            ("weather", "uvx", &["mcp-weather"], None),
+        ];
+        for (server_id, command, args, expected) in cases {
+            let server_config = ClientServerConfig {
+                command: command.to_string(),
+                args: strings(args),
+                env: HashMap::new(),
+                command_creator: String::new(),
+                input_args: vec![],
+                catalog_id: None,
+                url: None,
+                other_fields: HashMap::new(),
+            };
+            let found = match_catalog(&catalog, server_id, &server_config)
+                .map(|found| (found.catalog_id, (found.score * 100.0).round() / 100.0));
+            let expected = expected.map(|(catalog_id, score)| (catalog_id.to_string(), score));
+            assert_eq!(found, expected, "{} {:?}", server_id, args);
+        }
+    }
+
+    #[test]
+    fn trailing_args_follow_the_catalog_package() {
+        let package: &[&str] = &["-y", "@acme/files"];
+        let cases: [(&[&str], &[&str], &[&str]); 6] = [
+            (
+                package,
+                &["-y", "@acme/files", "/data", "--ro"],
+                &["/data", "--ro"],
+            ),
+            (package, &["-y", "@acme/files@1.2.0", "/data"], &["/data"]),
+            (package, &["-c", "npx -y @acme/files /data"], &["/data"]),
+            (package, &["-y", "@acme/files"], &[]),
+            // A different package that merely starts the same.
+            (package, &["-y", "@acme/files-pro", "/data"], &[]),
+            // No package in the catalog's arguments to look for.
+            (&["--stdio"], &["-y", "@acme/files", "/data"], &[]),
+        ];
+        for (catalog_args, args, expected) in cases {
+            assert_eq!(
+                trailing_args(&strings(catalog_args), &strings(args)),
+                strings(expected),
+                "{:?}",
+                args
+            );
+        }
+    }
+
+    #[test]
+    fn input_args_are_split_from_trailing_args() {
+        let input_args: Vec<InputArg> = serde_json::from_value(json!([
+            { "name": "root", "class": "DirectoryPath" },
+            { "name": "db", "flag": "--db" },
+            { "name": "mode", "flag": "--mode=" },
+            { "name": "readOnly", "flag": "--ro", "class": "Switch" },
+            { "name": "extra", "multiplicity": "Multiple" },
+        ]))
+        .unwrap();
+        let cases: [(&[&str], [&[&str]; 5]); 4] = [
+            (
+                &["--db", "x.db", "/data", "--mode=fast", "--ro", "a", "b"],
+                [&["/data"], &["x.db"], &["fast"], &["true"], &["a", "b"]],
+            ),
+            (&["/data"], [&["/data"], &[], &[], &[], &[]]),
+            (&[], [&[], &[], &[], &[], &[]]),
+            // A flag at the end with no value after it.
+            (&["/data", "--db"], [&["/data"], &[], &[], &[], &[]]),
+        ];
+        for (tokens, expected) in cases {
+            let split = split_input_args(&input_args, strings(tokens));
+            let values: Vec<Vec<String>> = split.into_iter().map(|arg| arg.value).collect();
+            let expected: Vec<Vec<String>> =
+                expected.iter().map(|values| strings(values)).collect();
+            assert_eq!(values, expected, "{:?}", tokens);
+        }
+    }
+
+    #[test]
+    fn disabled_servers_come_back_as_they_were() {
+        let target = ClientTarget::new(Some("claude".to_string()), None);
+        let other = ClientTarget::new(Some("cursor".to_string()), None);
+        let entry = json!({
+            "command": "npx",
+            "args": ["-y", "@acme/files", "/data"],
+            "env": { "LOG_LEVEL": "debug" },
+            "commandCreator": "MCPHub",
+            "inputArgs": [{ "name": "root", "value": ["/data"] }],
+            "disabled": false,
+        });
+        let mut servers: HashMap<String, ClientServerConfig> =
+            serde_json::from_value(json!({ "files": entry })).unwrap();
+
+        let before = serde_json::to_value(&servers["files"]).unwrap();
+        let config = servers.remove("files").unwrap();
+        let mut parked = vec![];
+        park(&mut parked, &target, "files".to_string(), config.clone());
+        park(&mut parked, &other, "files".to_string(), config);
+        // As kept under PARKED_SERVERS_KEY.
+        let stored = serde_json::to_value(&parked).unwrap();
+        let mut parked: Vec<ParkedServer> = serde_json::from_value(stored).unwrap();
+
+        let config = take_parked(&mut parked, &target, "files").unwrap();
+        restore_parked(&mut servers, "files", config).unwrap();
+        assert_eq!(serde_json::to_value(&servers["files"]).unwrap(), before);
+        assert_eq!(parked.len(), 1);
+        assert!(parked[0].is_for(&other, "files"));
+        assert!(take_parked(&mut parked, &target, "files").is_none());
+    }
+
+    #[test]
+    fn disabled_servers_do_not_replace_ids_taken_since() {
+        let target = ClientTarget::new(Some("claude".to_string()), None);
+        let mut parked = vec![];
+        let config: ClientServerConfig =
+            serde_json::from_value(json!({ "command": "npx", "commandCreator": "MCPHub" }))
+                .unwrap();
+        park(&mut parked, &target, "files".to_string(), config.clone());
+        park(&mut parked, &target, "files".to_string(), config.clone());
+        assert_eq!(parked.len(), 1);
+
+        let mut servers: HashMap<String, ClientServerConfig> =
+            serde_json::from_value(json!({ "files": { "command": "docker" } })).unwrap();
+        let error = restore_parked(&mut servers, "files", config).unwrap_err();
+        assert!(error.to_string().contains("files is already configured"));
+        assert_eq!(servers["files"].command, "docker");
+    }
+
+    #[test]
+    fn instance_names_become_slugs() {
+        let cases: [(Option<&str>, Option<&str>); 9] = [
+            (None, Some("github")),
+            (Some(""), Some("github")),
+            (Some("  "), Some("github")),
+            (Some("Work"), Some("github-work")),
+            (Some("My Work / Repo!"), Some("github-my-work-repo")),
+            (Some("--team--2--"), Some("github-team-2")),
+            // Only ASCII letters and digits are kept.
+            (Some("Café Team"), Some("github-caf-team")),
+            (Some("日本語"), None),
+            (Some("!!!"), None),
+        ];
+        for (name, expected) in cases {
+            let id = instance_id("github", name).ok();
+            assert_eq!(id.as_deref(), expected, "{:?}", name);
+        }
+    }
+
+    #[test]
+    fn named_instances_refuse_ids_already_configured() {
+        let servers: HashMap<String, ClientServerConfig> = serde_json::from_value(json!({
+            "github-work": { "command": "npx", "commandCreator": "MCPHub" },
+            "github-work-2": { "command": "docker" },
+        }))
+        .unwrap();
+        // Names that differ only in case or punctuation share an id.
+        let work = instance_id("github", Some("work")).unwrap();
+        assert_eq!(instance_id("github", Some("Work!")).unwrap(), work);
+        assert!(check_instance_free(&servers, &work).is_err());
+        // Entries written by hand are no more replaced than MCPHub's own.
+        let hand_written = instance_id("github", Some("Work 2")).unwrap();
+        assert!(check_instance_free(&servers, &hand_written).is_err());
+        let home = instance_id("github", Some("home")).unwrap();
+        check_instance_free(&servers, &home).unwrap();
     }
-    true
 }
@@ -1,38 +1,186 @@
 use super::core::{
+    adopt_server_function, disable_server_function, enable_server_function,
     install_server_function, load_all_frontend_servers, load_all_installed_frontend_servers,
-    uninstall_server_function, update_server_function, FrontendServer,
+    load_unmanaged_servers, preview_install_server_function, preview_uninstall_server_function,
+    preview_update_server_function, uninstall_server_function, update_server_function,
+    ChangePreview, ConfigChangeResult, FrontendServer, UnmanagedServer,
 };
+use crate::clients::{ClientTarget, ConfigScope};
+use crate::error::AppError;
 use log::debug;
-use std::collections::HashMap;
-use tauri_plugin_store::StoreExt;
+use std::collections::{BTreeMap, HashMap};
 
 #[tauri::command]
-pub async fn get_servers(app_handle: tauri::AppHandle) -> Vec<FrontendServer> {
+pub async fn get_servers(
+    app_handle: tauri::AppHandle,
+    client_id: Option<String>,
+    scope: Option<ConfigScope>,
+) -> Result<Vec<FrontendServer>, AppError> {
     debug!("get_servers view");
-    load_all_frontend_servers(&app_handle).await
+    let target = ClientTarget::new(client_id, scope);
+    load_all_frontend_servers(&app_handle, &target)
+        .await
+        .map_err(AppError::from)
 }
 
 #[tauri::command]
-pub async fn get_installed_servers(app_handle: tauri::AppHandle) -> Vec<FrontendServer> {
-    load_all_installed_frontend_servers(&app_handle).await
+pub async fn get_installed_servers(
+    app_handle: tauri::AppHandle,
+    client_id: Option<String>,
+    scope: Option<ConfigScope>,
+) -> Result<Vec<FrontendServer>, AppError> {
+    let target = ClientTarget::new(client_id, scope);
+    load_all_installed_frontend_servers(&app_handle, &target)
+        .await
+        .map_err(AppError::from)
 }
 
 #[tauri::command]
-pub async fn install_server(app_handle: tauri::AppHandle, server_id: &str) -> Result<bool, String> {
-    Ok(install_server_function(&app_handle, server_id, None, None).await)
+pub async fn install_server(
+    app_handle: tauri::AppHandle,
+    client_id: Option<String>,
+    scope: Option<ConfigScope>,
+    server_id: &str,
+    instance_name: Option<String>,
+) -> Result<ConfigChangeResult, AppError> {
+    let target = ClientTarget::new(client_id, scope);
+    install_server_function(
+        &app_handle,
+        &target,
+        server_id,
+        instance_name.as_deref(),
+        None,
+        None,
+    )
+    .await
+    .map_err(AppError::from)
 }
 
 #[tauri::command]
 pub async fn update_server(
     app_handle: tauri::AppHandle,
+    client_id: Option<String>,
+    scope: Option<ConfigScope>,
     server_id: &str,
     env: Option<HashMap<String, String>>,
-    input_arg: Option<Vec<String>>,
-) -> Result<bool, String> {
-    Ok(update_server_function(&app_handle, server_id, env, input_arg).await)
+    input_args: Option<BTreeMap<String, Vec<String>>>,
+) -> Result<ConfigChangeResult, AppError> {
+    let target = ClientTarget::new(client_id, scope);
+    update_server_function(&app_handle, &target, server_id, env, input_args)
+        .await
+        .map_err(AppError::from)
 }
 
 #[tauri::command]
-pub async fn uninstall_server(server_id: &str) -> Result<bool, String> {
-    Ok(uninstall_server_function(server_id).await)
+pub async fn uninstall_server(
+    app_handle: tauri::AppHandle,
+    client_id: Option<String>,
+    scope: Option<ConfigScope>,
+    server_id: &str,
+) -> Result<ConfigChangeResult, AppError> {
+    let target = ClientTarget::new(client_id, scope);
+    uninstall_server_function(&app_handle, &target, server_id)
+        .await
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn get_unmanaged_servers(
+    app_handle: tauri::AppHandle,
+    client_id: Option<String>,
+    scope: Option<ConfigScope>,
+) -> Result<Vec<UnmanagedServer>, AppError> {
+    let target = ClientTarget::new(client_id, scope);
+    load_unmanaged_servers(&app_handle, &target)
+        .await
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn adopt_server(
+    app_handle: tauri::AppHandle,
+    client_id: Option<String>,
+    scope: Option<ConfigScope>,
+    server_id: &str,
+    catalog_id: Option<String>,
+) -> Result<(), AppError> {
+    let target = ClientTarget::new(client_id, scope);
+    adopt_server_function(&app_handle, &target, server_id, catalog_id)
+        .await
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn disable_server(
+    app_handle: tauri::AppHandle,
+    client_id: Option<String>,
+    scope: Option<ConfigScope>,
+    server_id: &str,
+) -> Result<ConfigChangeResult, AppError> {
+    let target = ClientTarget::new(client_id, scope);
+    disable_server_function(&app_handle, &target, server_id)
+        .await
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn enable_server(
+    app_handle: tauri::AppHandle,
+    client_id: Option<String>,
+    scope: Option<ConfigScope>,
+    server_id: &str,
+) -> Result<ConfigChangeResult, AppError> {
+    let target = ClientTarget::new(client_id, scope);
+    enable_server_function(&app_handle, &target, server_id)
+        .await
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn preview_install_server(
+    app_handle: tauri::AppHandle,
+    client_id: Option<String>,
+    scope: Option<ConfigScope>,
+    server_id: &str,
+    instance_name: Option<String>,
+) -> Result<ChangePreview, AppError> {
+    let target = ClientTarget::new(client_id, scope);
+    preview_install_server_function(
+        &app_handle,
+        &target,
+        server_id,
+        instance_name.as_deref(),
+        None,
+        None,
+    )
+    .await
+    .map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn preview_update_server(
+    app_handle: tauri::AppHandle,
+    client_id: Option<String>,
+    scope: Option<ConfigScope>,
+    server_id: &str,
+    env: Option<HashMap<String, String>>,
+    input_args: Option<BTreeMap<String, Vec<String>>>,
+) -> Result<ChangePreview, AppError> {
+    let target = ClientTarget::new(client_id, scope);
+    preview_update_server_function(&app_handle, &target, server_id, env, input_args)
+        .await
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn preview_uninstall_server(
+    app_handle: tauri::AppHandle,
+    client_id: Option<String>,
+    scope: Option<ConfigScope>,
+    server_id: &str,
+) -> Result<ChangePreview, AppError> {
+    let target = ClientTarget::new(client_id, scope);
+    preview_uninstall_server_function(&app_handle, &target, server_id)
+        .await
+        .map_err(AppError::from)
 }
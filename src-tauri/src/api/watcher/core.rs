@@ -0,0 +1,317 @@
+use crate::api::history::core::{diff_servers, ChangeKind};
+use crate::clients::{
+    all_clients, get_client, read_config_file, ClientServerConfig, ClientTarget,
+    ConfigConflictError, ConfigScope, McpClient,
+};
+use anyhow::Result;
+use log::{debug, warn};
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use tauri::{Emitter, Manager};
+
+pub const CONFIG_CHANGED_EVENT: &str = "client-config-changed";
+pub const CONFIG_CONFLICT_EVENT: &str = "client-config-conflict";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConfigChangedPayload {
+    #[serde(rename = "clientId")]
+    client_id: String,
+    scope: ConfigScope,
+    #[serde(rename = "configPath")]
+    config_path: String,
+    added: Vec<String>,
+    removed: Vec<String>,
+    changed: Vec<String>,
+    /// False when the change is one MCPHub wrote itself.
+    external: bool,
+    /// Set when the new contents could not be parsed; the server lists are empty then.
+    #[serde(rename = "parseError")]
+    parse_error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConfigConflictPayload {
+    #[serde(rename = "configPath")]
+    config_path: String,
+    message: String,
+}
+
+struct WatchedTarget {
+    target: ClientTarget,
+    client_id: String,
+    /// Servers as of the last time the file parsed.
+    servers: HashMap<String, ClientServerConfig>,
+    parse_error: Option<String>,
+}
+
+#[derive(Default)]
+struct WatchedConfig {
+    targets: Vec<WatchedTarget>,
+    /// Contents MCPHub last wrote to the file, to tell its own writes from others'.
+    last_written: Option<String>,
+}
+
+#[derive(Default)]
+struct WatcherState {
+    watcher: Option<RecommendedWatcher>,
+    /// Directories handed to the OS watcher. Parents are watched rather than the
+    /// files, since editors and MCPHub itself replace config files by renaming.
+    /// Until a parent exists, its closest existing ancestor is watched instead.
+    directories: HashSet<PathBuf>,
+    configs: HashMap<PathBuf, WatchedConfig>,
+}
+
+impl WatcherState {
+    /// Watches the directory each config needs now, and returns the configs whose
+    /// own directory was only just created: they may have been written before the
+    /// watch was in place, so they need a re-read.
+    fn watch_directories(&mut self) -> Vec<PathBuf> {
+        let mut added = HashSet::new();
+        for config_path in self.configs.keys() {
+            let Some(directory) = watch_directory(config_path) else {
+                continue;
+            };
+            if self.directories.contains(directory) {
+                continue;
+            }
+            if let Some(watcher) = self.watcher.as_mut() {
+                if let Err(e) = watcher.watch(directory, RecursiveMode::NonRecursive) {
+                    warn!("Failed to watch {}: {}", directory.to_string_lossy(), e);
+                    continue;
+                }
+            }
+            self.directories.insert(directory.to_path_buf());
+            added.insert(directory.to_path_buf());
+        }
+        self.configs
+            .keys()
+            .filter(|config_path| config_path.parent().is_some_and(|dir| added.contains(dir)))
+            .cloned()
+            .collect()
+    }
+}
+
+/// The directory to watch for `config_path`: its parent or, until that exists, the
+/// closest ancestor that does, so the parent's creation shows up as an event.
+fn watch_directory(config_path: &Path) -> Option<&Path> {
+    config_path
+        .ancestors()
+        .skip(1)
+        .find(|directory| directory.is_dir())
+}
+
+/// Managed state holding the file watcher and what it last saw of each config.
+#[derive(Default)]
+pub struct ConfigWatcher {
+    state: Mutex<WatcherState>,
+}
+
+/// Starts watching the user scope config of every registered client.
+pub fn start_watching(app_handle: &tauri::AppHandle) -> Result<()> {
+    app_handle.manage(ConfigWatcher::default());
+    let events_handle = app_handle.clone();
+    let watcher = notify::recommended_watcher(move |event: notify::Result<Event>| match event {
+        Ok(event) => handle_event(&events_handle, &event),
+        Err(e) => warn!("Config watcher error: {}", e),
+    })?;
+    if let Some(config_watcher) = app_handle.try_state::<ConfigWatcher>() {
+        config_watcher
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .watcher = Some(watcher);
+    }
+
+    for client in all_clients(app_handle) {
+        let target = ClientTarget::new(Some(client.id().to_string()), None);
+        if let Err(e) = watch_client(app_handle, client.as_ref(), &target) {
+            warn!("Not watching {}: {}", client.name(), e);
+        }
+    }
+    Ok(())
+}
+
+/// Adds the config `target` points at to the watch list, e.g. a workspace the user
+/// just opened. Watching a target twice is harmless.
+pub async fn watch_client_config_function(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+) -> Result<()> {
+    let client = get_client(app_handle, target)?;
+    watch_client(app_handle, client.as_ref(), target)
+}
+
+fn watch_client(
+    app_handle: &tauri::AppHandle,
+    client: &dyn McpClient,
+    target: &ClientTarget,
+) -> Result<()> {
+    let Some(config_watcher) = app_handle.try_state::<ConfigWatcher>() else {
+        return Ok(());
+    };
+    let config_path = client.config_path()?;
+    let (servers, parse_error) =
+        match read_config_file(&config_path).and_then(|text| client.parse_servers(&text)) {
+            Ok(servers) => (servers, None),
+            Err(e) => (HashMap::new(), Some(e.to_string())),
+        };
+
+    let mut state = config_watcher
+        .state
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    let watched = state.configs.entry(config_path).or_default();
+    let client_id = client.id().to_string();
+    if !watched
+        .targets
+        .iter()
+        .any(|watched| watched.client_id == client_id && watched.target.scope == target.scope)
+    {
+        watched.targets.push(WatchedTarget {
+            target: target.clone(),
+            client_id,
+            servers,
+            parse_error,
+        });
+    }
+    state.watch_directories();
+    Ok(())
+}
+
+/// Records contents MCPHub is about to write, so the resulting event is not reported
+/// as an external edit.
+pub fn note_own_write(app_handle: &tauri::AppHandle, config_path: &Path, contents: &str) {
+    if let Some(config_watcher) = app_handle.try_state::<ConfigWatcher>() {
+        let mut state = config_watcher
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(watched) = state.configs.get_mut(config_path) {
+            watched.last_written = Some(contents.to_string());
+        }
+    }
+}
+
+/// Tells the UI that a write was abandoned because the file changed underneath it.
+pub fn notify_conflict(app_handle: &tauri::AppHandle, conflict: &ConfigConflictError) {
+    let payload = ConfigConflictPayload {
+        config_path: conflict.path.to_string_lossy().to_string(),
+        message: conflict.to_string(),
+    };
+    if let Err(e) = app_handle.emit(CONFIG_CONFLICT_EVENT, payload) {
+        warn!("Failed to emit {}: {}", CONFIG_CONFLICT_EVENT, e);
+    }
+}
+
+fn handle_event(app_handle: &tauri::AppHandle, event: &Event) {
+    // A directory a config lives in may have just been created.
+    let appeared = match app_handle.try_state::<ConfigWatcher>() {
+        Some(config_watcher) => config_watcher
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .watch_directories(),
+        None => vec![],
+    };
+    for path in event.paths.iter().chain(&appeared) {
+        for payload in refresh_config(app_handle, path) {
+            debug!(
+                "{} changed: +{:?} -{:?} ~{:?}",
+                payload.config_path, payload.added, payload.removed, payload.changed
+            );
+            if let Err(e) = app_handle.emit(CONFIG_CHANGED_EVENT, payload) {
+                warn!("Failed to emit {}: {}", CONFIG_CHANGED_EVENT, e);
+            }
+        }
+    }
+}
+
+/// Re-reads a watched config and returns one payload per target whose servers differ
+/// from what was seen last. Events that leave the servers as they were yield nothing,
+/// which also folds the bursts of events a single save produces.
+fn refresh_config(app_handle: &tauri::AppHandle, config_path: &Path) -> Vec<ConfigChangedPayload> {
+    let Some(config_watcher) = app_handle.try_state::<ConfigWatcher>() else {
+        return vec![];
+    };
+    let mut state = config_watcher
+        .state
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    let Some(watched) = state.configs.get_mut(config_path) else {
+        return vec![];
+    };
+    let text = match read_config_file(config_path) {
+        Ok(text) => text,
+        Err(e) => {
+            warn!("Failed to read {}: {}", config_path.to_string_lossy(), e);
+            return vec![];
+        }
+    };
+    let external = watched.last_written.as_deref() != Some(text.as_str());
+
+    let mut payloads = vec![];
+    for watched_target in &mut watched.targets {
+        let Ok(client) = get_client(app_handle, &watched_target.target) else {
+            continue;
+        };
+        let (servers, parse_error) = match client.parse_servers(&text) {
+            Ok(servers) => (servers, None),
+            // A file that doesn't parse keeps the last good snapshot, so the diff is
+            // against it once the file is fixed.
+            Err(e) => (watched_target.servers.clone(), Some(e.to_string())),
+        };
+        let changes = diff_servers(&watched_target.servers, &servers);
+        if changes.is_empty() && parse_error == watched_target.parse_error {
+            continue;
+        }
+        let ids_of = |kind: ChangeKind| {
+            changes
+                .iter()
+                .filter(|change| change.kind == kind)
+                .map(|change| change.server_id.clone())
+                .collect()
+        };
+        payloads.push(ConfigChangedPayload {
+            client_id: watched_target.client_id.clone(),
+            scope: watched_target.target.scope.clone(),
+            config_path: config_path.to_string_lossy().to_string(),
+            added: ids_of(ChangeKind::Added),
+            removed: ids_of(ChangeKind::Removed),
+            changed: ids_of(ChangeKind::Modified),
+            external,
+            parse_error: parse_error.clone(),
+        });
+        watched_target.servers = servers;
+        watched_target.parse_error = parse_error;
+    }
+    payloads
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_config_directories_are_watched_once_created() {
+        let home = tempfile::tempdir().unwrap();
+        let config_path = home.path().join(".cursor").join("mcp.json");
+        let mut state = WatcherState::default();
+        state
+            .configs
+            .insert(config_path.clone(), WatchedConfig::default());
+
+        assert!(state.watch_directories().is_empty());
+        assert_eq!(
+            state.directories,
+            HashSet::from([home.path().to_path_buf()])
+        );
+
+        std::fs::create_dir(home.path().join(".cursor")).unwrap();
+        assert_eq!(state.watch_directories(), vec![config_path]);
+        assert!(state.directories.contains(&home.path().join(".cursor")));
+        assert!(state.watch_directories().is_empty());
+    }
+}
@@ -0,0 +1,15 @@
+use super::core::watch_client_config_function;
+use crate::clients::{ClientTarget, ConfigScope};
+use crate::error::AppError;
+
+#[tauri::command]
+pub async fn watch_client_config(
+    app_handle: tauri::AppHandle,
+    client_id: Option<String>,
+    scope: Option<ConfigScope>,
+) -> Result<(), AppError> {
+    let target = ClientTarget::new(client_id, scope);
+    watch_client_config_function(&app_handle, &target)
+        .await
+        .map_err(AppError::from)
+}
@@ -0,0 +1,92 @@
+use super::{jsonc_config, ClientServerConfig, ConfigScope, McpClient};
+use crate::utils::os::get_home;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+pub const CLIENT_ID: &str = "claude_code";
+
+/// Claude Code reads user servers from the top level `mcpServers` of `~/.claude.json`,
+/// local servers from `projects.<workspace>.mcpServers` in the same file, and project
+/// servers from a `.mcp.json` committed to the repository.
+///
+/// `~/.claude.json` holds a lot of unrelated state that Claude Code rewrites often, so
+/// it is edited in place through the JSONC helpers rather than re-serialized.
+pub struct ClaudeCodeClient {
+    scope: ConfigScope,
+}
+
+impl ClaudeCodeClient {
+    pub fn new(scope: ConfigScope) -> Self {
+        ClaudeCodeClient { scope }
+    }
+
+    fn servers_path(&self) -> Vec<String> {
+        match &self.scope {
+            ConfigScope::Local(workspace) => vec![
+                "projects".to_string(),
+                workspace.to_string_lossy().to_string(),
+                "mcpServers".to_string(),
+            ],
+            _ => vec!["mcpServers".to_string()],
+        }
+    }
+}
+
+impl McpClient for ClaudeCodeClient {
+    fn id(&self) -> &str {
+        CLIENT_ID
+    }
+
+    fn name(&self) -> &str {
+        "Claude Code"
+    }
+
+    fn scopes(&self) -> &'static [&'static str] {
+        &["user", "project", "local"]
+    }
+
+    fn is_installed(&self) -> bool {
+        get_home()
+            .map(|home| home.join(".claude.json").exists() || home.join(".claude").exists())
+            .unwrap_or(false)
+    }
+
+    fn config_path(&self) -> Result<PathBuf> {
+        match &self.scope {
+            ConfigScope::Project(workspace) => Ok(workspace.join(".mcp.json")),
+            _ => Ok(get_home()?.join(".claude.json")),
+        }
+    }
+
+    fn parse_servers(&self, text: &str) -> Result<HashMap<String, ClientServerConfig>> {
+        let servers_path = self.servers_path();
+        let servers_path: Vec<&str> = servers_path.iter().map(String::as_str).collect();
+        Ok(jsonc_config::entries(text, &servers_path)?
+            .into_iter()
+            .filter_map(|(server_id, entry)| {
+                ClientServerConfig::from_entry(&entry)
+                    .map(|server_config| (server_id, server_config))
+            })
+            .collect())
+    }
+
+    fn render_servers(
+        &self,
+        text: &str,
+        servers: &HashMap<String, ClientServerConfig>,
+    ) -> Result<String> {
+        let servers_path = self.servers_path();
+        let servers_path: Vec<&str> = servers_path.iter().map(String::as_str).collect();
+        let mut entries = HashMap::new();
+        for (server_id, server_config) in servers {
+            entries.insert(server_id.clone(), serde_json::to_value(server_config)?);
+        }
+        jsonc_config::apply_entries(
+            text.to_string(),
+            &servers_path,
+            &entries,
+            ClientServerConfig::from_entry,
+        )
+    }
+}
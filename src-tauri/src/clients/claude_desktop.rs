@@ -0,0 +1,52 @@
+use super::{ClientConfig, ClientServerConfig, McpClient};
+use crate::utils::os::get_config_dir;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+pub const CLIENT_ID: &str = "claude_desktop";
+
+pub struct ClaudeDesktopClient;
+
+impl McpClient for ClaudeDesktopClient {
+    fn id(&self) -> &str {
+        CLIENT_ID
+    }
+
+    fn name(&self) -> &str {
+        "Claude Desktop"
+    }
+
+    fn config_path(&self) -> Result<PathBuf> {
+        Ok(get_config_dir()?
+            .join("Claude")
+            .join("claude_desktop_config.json"))
+    }
+
+    fn process_names(&self) -> &'static [&'static str] {
+        #[cfg(target_os = "macos")]
+        {
+            &["Claude"]
+        }
+        #[cfg(target_os = "windows")]
+        {
+            &["Claude.exe"]
+        }
+        #[cfg(target_os = "linux")]
+        {
+            &["claude-desktop"]
+        }
+    }
+
+    fn parse_servers(&self, text: &str) -> Result<HashMap<String, ClientServerConfig>> {
+        ClientConfig::parse_servers(text)
+    }
+
+    fn render_servers(
+        &self,
+        text: &str,
+        servers: &HashMap<String, ClientServerConfig>,
+    ) -> Result<String> {
+        ClientConfig::render_servers(text, servers)
+    }
+}
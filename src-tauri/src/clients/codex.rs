@@ -0,0 +1,351 @@
+use super::{ClientServerConfig, McpClient, MCPHUB_CREATOR};
+use crate::api::servers::core::InputArg;
+use crate::launcher::Launcher;
+use crate::utils::os::get_home;
+use anyhow::{bail, Result};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use toml_edit::{value, Array, DocumentMut, InlineTable, Item, Table, TableLike, Value};
+
+pub const CLIENT_ID: &str = "codex";
+
+/// Codex has no field for MCPHub's bookkeeping and may reject unknown keys, so
+/// entries MCPHub owns are tagged with this comment above their table instead,
+/// followed by `: <catalog id>` for instances not keyed by their catalog id.
+const MANAGED_MARKER: &str = "# Managed by MCPHub";
+
+/// The Codex CLI reads `[mcp_servers.<name>]` tables from `config.toml`. Edits go
+/// through `toml_edit` so unrelated tables and comments survive.
+pub struct CodexClient;
+
+impl McpClient for CodexClient {
+    fn id(&self) -> &str {
+        CLIENT_ID
+    }
+
+    fn name(&self) -> &str {
+        "Codex CLI"
+    }
+
+    fn config_path(&self) -> Result<PathBuf> {
+        let codex_home = match std::env::var_os("CODEX_HOME") {
+            Some(dir) => PathBuf::from(dir),
+            None => get_home()?.join(".codex"),
+        };
+        Ok(codex_home.join("config.toml"))
+    }
+
+    fn parse_servers(&self, text: &str) -> Result<HashMap<String, ClientServerConfig>> {
+        let document = text.parse::<DocumentMut>()?;
+        let Some(servers) = document.get("mcp_servers").and_then(Item::as_table_like) else {
+            return Ok(HashMap::new());
+        };
+        Ok(servers
+            .iter()
+            .filter_map(|(server_id, item)| {
+                from_codex_entry(item).map(|server_config| (server_id.to_string(), server_config))
+            })
+            .collect())
+    }
+
+    fn render_servers(
+        &self,
+        text: &str,
+        servers: &HashMap<String, ClientServerConfig>,
+    ) -> Result<String> {
+        let mut document = text.parse::<DocumentMut>()?;
+        // Entries of an inline `mcp_servers` table have no room for the marker either.
+        let managed = servers
+            .values()
+            .any(|server_config| server_config.command_creator == MCPHUB_CREATOR);
+        let inline = document
+            .get("mcp_servers")
+            .and_then(Item::as_inline_table)
+            .filter(|_| managed)
+            .cloned();
+        if let Some(inline) = inline {
+            document.insert("mcp_servers", Item::Table(inline.into_table()));
+            clear_key_decor(document.as_table_mut(), "mcp_servers");
+        }
+        let servers_item = document.entry("mcp_servers").or_insert_with(|| {
+            let mut table = Table::new();
+            table.set_implicit(true);
+            Item::Table(table)
+        });
+        let Some(existing) = servers_item.as_table_like_mut() else {
+            bail!("`mcp_servers` is not a table");
+        };
+
+        let stale: Vec<String> = existing
+            .iter()
+            .filter(|(server_id, item)| {
+                !servers.contains_key(*server_id) && from_codex_entry(item).is_some()
+            })
+            .map(|(server_id, _)| server_id.to_string())
+            .collect();
+        for server_id in stale {
+            existing.remove(&server_id);
+        }
+
+        for (server_id, server_config) in servers {
+            let unchanged = existing
+                .get(server_id)
+                .and_then(from_codex_entry)
+                .is_some_and(|current| same_entry(&current, server_config));
+            if unchanged {
+                continue;
+            }
+            let was_table = existing
+                .get(server_id)
+                .and_then(Item::as_table)
+                .is_some_and(|table| !table.is_dotted());
+            let item = existing
+                .entry(server_id)
+                .or_insert_with(|| Item::Table(Table::new()));
+            update_codex_entry(item, server_config);
+            if server_config.command_creator == MCPHUB_CREATOR && !was_table {
+                clear_key_decor(existing, server_id);
+            }
+        }
+
+        Ok(document.to_string())
+    }
+}
+
+fn from_codex_entry(item: &Item) -> Option<ClientServerConfig> {
+    let table = item.as_table_like()?;
+    let command = table.get("command")?.as_str()?.to_string();
+    let args = table
+        .get("args")
+        .and_then(Item::as_array)
+        .map(|args| {
+            args.iter()
+                .filter_map(|arg| arg.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default();
+    let env = table
+        .get("env")
+        .and_then(Item::as_table_like)
+        .map(|env| {
+            env.iter()
+                .filter_map(|(key, val)| val.as_str().map(|val| (key.to_string(), val.to_string())))
+                .collect()
+        })
+        .unwrap_or_default();
+    let marker = item
+        .as_table()
+        .and_then(|table| table.decor().prefix())
+        .and_then(|prefix| prefix.as_str())
+        .and_then(|prefix| prefix.lines().find(|line| line.starts_with(MANAGED_MARKER)));
+    let managed = marker.is_some();
+    let catalog_id = marker
+        .and_then(|marker| marker[MANAGED_MARKER.len()..].strip_prefix(": "))
+        .map(|catalog_id| catalog_id.trim().to_string());
+
+    let mut server_config = ClientServerConfig {
+        command,
+        args,
+        env,
+        command_creator: if managed {
+            MCPHUB_CREATOR.to_string()
+        } else {
+            String::new()
+        },
+        input_args: vec![],
+        catalog_id,
+        url: None,
+        other_fields: HashMap::new(),
+    };
+    // There is no key for the input arguments either, but the launcher keeps them.
+    if let Some(launcher) = Launcher::from_entry(&server_config) {
+        server_config.input_args = launcher
+            .spec
+            .input_args
+            .into_iter()
+            .map(|(name, value)| InputArg::with_values(name, value))
+            .collect();
+    }
+    Some(server_config)
+}
+
+fn same_entry(current: &ClientServerConfig, wanted: &ClientServerConfig) -> bool {
+    current.command == wanted.command
+        && current.args == wanted.args
+        && current.env == wanted.env
+        && (current.command_creator == MCPHUB_CREATOR) == (wanted.command_creator == MCPHUB_CREATOR)
+        && current.catalog_id == wanted.catalog_id
+}
+
+/// Rewrites the MCP keys of an entry in place, keeping any other keys and comments.
+/// MCPHub entries become `[mcp_servers.<name>]` tables, the only form with a place
+/// for the marker.
+fn update_codex_entry(item: &mut Item, server_config: &ClientServerConfig) {
+    let managed = server_config.command_creator == MCPHUB_CREATOR;
+    *item = match std::mem::take(item) {
+        Item::Value(Value::InlineTable(inline)) if managed => Item::Table(inline.into_table()),
+        Item::Table(mut table) if managed && table.is_dotted() => {
+            table.set_dotted(false);
+            Item::Table(table)
+        }
+        item if item.is_table_like() => item,
+        _ => Item::Table(Table::new()),
+    };
+    if let Some(table) = item.as_table_mut() {
+        let marker = managed.then(|| match &server_config.catalog_id {
+            Some(catalog_id) => format!("{}: {}", MANAGED_MARKER, catalog_id),
+            None => MANAGED_MARKER.to_string(),
+        });
+        set_marker(table, marker);
+    }
+    let Some(table) = item.as_table_like_mut() else {
+        return;
+    };
+
+    table.insert("command", value(server_config.command.as_str()));
+    table.insert(
+        "args",
+        value(Array::from_iter(
+            server_config.args.iter().map(String::as_str),
+        )),
+    );
+    if server_config.env.is_empty() {
+        table.remove("env");
+    } else {
+        let mut keys: Vec<&String> = server_config.env.keys().collect();
+        keys.sort();
+        let mut env = InlineTable::new();
+        for key in keys {
+            env.insert(key, server_config.env[key].as_str().into());
+        }
+        table.insert("env", value(env));
+    }
+}
+
+/// Drops the spacing written around `key` for an inline value, which would otherwise
+/// end up inside the header of the table it became.
+fn clear_key_decor(table: &mut dyn TableLike, key: &str) {
+    if let Some(mut key) = table.key_mut(key) {
+        key.leaf_decor_mut().clear();
+        key.dotted_decor_mut().clear();
+    }
+}
+
+/// Puts `marker` above the table in place of any marker it has, or takes the marker
+/// away when there is none to put. Other comments stay as they are.
+fn set_marker(table: &mut Table, marker: Option<String>) {
+    let prefix = table
+        .decor()
+        .prefix()
+        .and_then(|prefix| prefix.as_str())
+        .unwrap_or_default()
+        .to_string();
+    let current = prefix.lines().find(|line| line.starts_with(MANAGED_MARKER));
+    if current == marker.as_deref() {
+        return;
+    }
+    let kept: Vec<&str> = prefix
+        .trim_end()
+        .lines()
+        .filter(|line| !line.starts_with(MANAGED_MARKER))
+        .collect();
+    let kept = kept.join("\n");
+    let prefix = match marker {
+        Some(marker) => format!("{}\n{}\n", kept.trim_end(), marker),
+        None if kept.trim().is_empty() => "\n".to_string(),
+        None => format!("{}\n", kept.trim_end()),
+    };
+    table.decor_mut().set_prefix(prefix);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(command: &str, managed: bool) -> ClientServerConfig {
+        ClientServerConfig {
+            command: command.to_string(),
+            args: vec![],
+            env: HashMap::new(),
+            command_creator: if managed {
+                MCPHUB_CREATOR.to_string()
+            } else {
+                String::new()
+            },
+            input_args: vec![],
+            catalog_id: None,
+            url: None,
+            other_fields: HashMap::new(),
+        }
+    }
+
+    fn render(text: &str, servers: &[(&str, ClientServerConfig)]) -> String {
+        let servers = servers
+            .iter()
+            .map(|(server_id, server_config)| (server_id.to_string(), server_config.clone()))
+            .collect();
+        CodexClient.render_servers(text, &servers).unwrap()
+    }
+
+    fn is_managed(text: &str, server_id: &str) -> bool {
+        CodexClient.parse_servers(text).unwrap()[server_id].command_creator == MCPHUB_CREATOR
+    }
+
+    #[test]
+    fn new_entries_are_marked_as_managed() {
+        let mut github = server("npx", true);
+        github.catalog_id = Some("github".to_string());
+        let rendered = render("model = \"o3\"\n", &[("work", github)]);
+        assert_eq!(
+            rendered,
+            "model = \"o3\"\n\n# Managed by MCPHub: github\n[mcp_servers.work]\ncommand = \"npx\"\nargs = []\n"
+        );
+        let parsed = CodexClient.parse_servers(&rendered).unwrap();
+        assert_eq!(parsed["work"].command_creator, MCPHUB_CREATOR);
+        assert_eq!(parsed["work"].catalog_id.as_deref(), Some("github"));
+    }
+
+    #[test]
+    fn inline_and_dotted_entries_become_marked_tables() {
+        let texts = [
+            "[mcp_servers]\ngithub = { command = \"docker\" }\n",
+            "mcp_servers.github = { command = \"docker\" }\n",
+            "mcp_servers = { github = { command = \"docker\" } }\n",
+            "[mcp_servers]\ngithub.command = \"docker\"\n",
+        ];
+        for text in texts {
+            assert!(!is_managed(text, "github"), "{}", text);
+            let rendered = render(text, &[("github", server("npx", true))]);
+            assert!(
+                is_managed(&rendered, "github"),
+                "{} became {}",
+                text,
+                rendered
+            );
+            assert!(rendered.contains("[mcp_servers.github]\n"), "{}", rendered);
+            assert_eq!(
+                CodexClient.parse_servers(&rendered).unwrap()["github"].command,
+                "npx"
+            );
+        }
+    }
+
+    #[test]
+    fn markers_go_away_with_their_entries() {
+        let text = "# Mine\n[mcp_servers.fetch]\ncommand = \"uvx\"\n\n# Managed by MCPHub\n[mcp_servers.github]\ncommand = \"npx\"\nargs = []\n";
+        assert!(is_managed(text, "github"));
+        let rendered = render(text, &[("fetch", server("uvx", false))]);
+        assert_eq!(rendered, "# Mine\n[mcp_servers.fetch]\ncommand = \"uvx\"\n");
+
+        let rendered = render(
+            text,
+            &[
+                ("fetch", server("uvx", false)),
+                ("github", server("npx", false)),
+            ],
+        );
+        assert!(!rendered.contains(MANAGED_MARKER));
+        assert!(!is_managed(&rendered, "github"));
+        assert!(rendered.starts_with("# Mine\n"));
+    }
+}
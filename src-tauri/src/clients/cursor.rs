@@ -0,0 +1,61 @@
+use super::{ClientConfig, ClientServerConfig, ConfigScope, McpClient};
+use crate::utils::os::get_home;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+pub const CLIENT_ID: &str = "cursor";
+
+pub struct CursorClient {
+    scope: ConfigScope,
+}
+
+impl CursorClient {
+    pub fn new(scope: ConfigScope) -> Self {
+        CursorClient { scope }
+    }
+}
+
+impl McpClient for CursorClient {
+    fn id(&self) -> &str {
+        CLIENT_ID
+    }
+
+    fn name(&self) -> &str {
+        "Cursor"
+    }
+
+    fn scopes(&self) -> &'static [&'static str] {
+        &["user", "project"]
+    }
+
+    fn is_installed(&self) -> bool {
+        get_home()
+            .map(|home| home.join(".cursor").exists())
+            .unwrap_or(false)
+    }
+
+    fn config_path(&self) -> Result<PathBuf> {
+        let base_dir = match &self.scope {
+            ConfigScope::Project(workspace) => workspace.clone(),
+            _ => get_home()?,
+        };
+        Ok(base_dir.join(".cursor").join("mcp.json"))
+    }
+
+    fn secret_reference(&self, _server_id: &str, key: &str) -> String {
+        format!("${{env:{}}}", key)
+    }
+
+    fn parse_servers(&self, text: &str) -> Result<HashMap<String, ClientServerConfig>> {
+        ClientConfig::parse_servers(text)
+    }
+
+    fn render_servers(
+        &self,
+        text: &str,
+        servers: &HashMap<String, ClientServerConfig>,
+    ) -> Result<String> {
+        ClientConfig::render_servers(text, servers)
+    }
+}
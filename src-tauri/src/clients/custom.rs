@@ -0,0 +1,416 @@
+use super::{jsonc_config, ClientServerConfig, McpClient};
+use crate::utils::os::{get_home, xdg_base_dir};
+use anyhow::{anyhow, bail, Context, Result};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Directory inside the MCPHub data dir that descriptor files are read from.
+pub const DESCRIPTORS_DIR: &str = "clients";
+
+/// Config file location per platform. `~` and `${VAR}` are expanded, and `%VAR%` on
+/// Windows. Unset XDG base directory variables stand for their usual directories.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PlatformPaths {
+    #[serde(default)]
+    macos: Option<String>,
+    #[serde(default)]
+    windows: Option<String>,
+    #[serde(default)]
+    linux: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FieldNames {
+    #[serde(default = "default_command_field")]
+    command: String,
+    #[serde(default = "default_args_field")]
+    args: String,
+    #[serde(default = "default_env_field")]
+    env: String,
+    #[serde(default = "default_url_field")]
+    url: String,
+}
+
+impl Default for FieldNames {
+    fn default() -> Self {
+        FieldNames {
+            command: default_command_field(),
+            args: default_args_field(),
+            env: default_env_field(),
+            url: default_url_field(),
+        }
+    }
+}
+
+fn default_command_field() -> String {
+    "command".to_string()
+}
+
+fn default_args_field() -> String {
+    "args".to_string()
+}
+
+fn default_env_field() -> String {
+    "env".to_string()
+}
+
+fn default_url_field() -> String {
+    "url".to_string()
+}
+
+fn default_servers_pointer() -> String {
+    "/mcpServers".to_string()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DescriptorFormat {
+    #[default]
+    Json,
+    Jsonc,
+}
+
+/// A user supplied description of a client MCPHub has no built-in adapter for, read
+/// from `<data dir>/clients/*.json`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClientDescriptor {
+    id: String,
+    name: String,
+    #[serde(rename = "configPath")]
+    config_path: PlatformPaths,
+    /// JSON pointer (RFC 6901) to the object holding the server entries.
+    #[serde(rename = "serversPointer", default = "default_servers_pointer")]
+    servers_pointer: String,
+    #[serde(default)]
+    fields: FieldNames,
+    #[serde(default)]
+    format: DescriptorFormat,
+}
+
+pub struct CustomClient {
+    descriptor: ClientDescriptor,
+}
+
+impl CustomClient {
+    pub fn new(descriptor: ClientDescriptor) -> Self {
+        CustomClient { descriptor }
+    }
+
+    fn servers_path(&self) -> Result<Vec<String>> {
+        parse_pointer(&self.descriptor.servers_pointer)
+    }
+
+    fn parse_entry(&self, entry: &Value) -> Option<ClientServerConfig> {
+        let fields = &self.descriptor.fields;
+        let object = entry.as_object()?;
+        let command = object.get(&fields.command).and_then(Value::as_str);
+        let url = object.get(&fields.url).and_then(Value::as_str);
+        if command.is_none() && url.is_none() {
+            return None;
+        }
+
+        // Keys that aren't mapped fields carry over as they are, MCPHub's own
+        // bookkeeping included.
+        let mut server_config = object.clone();
+        for field in [&fields.command, &fields.args, &fields.env, &fields.url] {
+            server_config.remove(field);
+        }
+        server_config.insert("command".to_string(), command.unwrap_or_default().into());
+        if let Some(args) = object.get(&fields.args) {
+            server_config.insert("args".to_string(), args.clone());
+        }
+        if let Some(env) = object.get(&fields.env) {
+            server_config.insert("env".to_string(), env.clone());
+        }
+        if let Some(url) = url {
+            server_config.insert("url".to_string(), url.into());
+        }
+        ClientServerConfig::from_entry(&Value::Object(server_config))
+    }
+
+    fn to_entry(&self, server_config: &ClientServerConfig) -> Result<Value> {
+        let fields = &self.descriptor.fields;
+        let Value::Object(mut object) = serde_json::to_value(server_config)? else {
+            bail!("Server config did not serialize to an object");
+        };
+        let mut entry = Map::new();
+        for (field, name) in [
+            ("command", &fields.command),
+            ("args", &fields.args),
+            ("env", &fields.env),
+            ("url", &fields.url),
+        ] {
+            if let Some(value) = object.remove(field) {
+                entry.insert(name.clone(), value);
+            }
+        }
+        entry.extend(object);
+        Ok(Value::Object(entry))
+    }
+}
+
+impl McpClient for CustomClient {
+    fn id(&self) -> &str {
+        &self.descriptor.id
+    }
+
+    fn name(&self) -> &str {
+        &self.descriptor.name
+    }
+
+    fn config_path(&self) -> Result<PathBuf> {
+        let paths = &self.descriptor.config_path;
+        #[cfg(target_os = "macos")]
+        let raw_path = paths.macos.as_deref();
+        #[cfg(target_os = "windows")]
+        let raw_path = paths.windows.as_deref();
+        #[cfg(target_os = "linux")]
+        let raw_path = paths.linux.as_deref();
+
+        let raw_path = raw_path.ok_or_else(|| {
+            anyhow!(
+                "{} has no config path for this platform",
+                self.descriptor.name
+            )
+        })?;
+        expand_path(raw_path)
+    }
+
+    fn parse_servers(&self, text: &str) -> Result<HashMap<String, ClientServerConfig>> {
+        let servers_path = self.servers_path()?;
+        let servers_path: Vec<&str> = servers_path.iter().map(String::as_str).collect();
+        Ok(jsonc_config::entries(text, &servers_path)?
+            .into_iter()
+            .filter_map(|(server_id, entry)| {
+                self.parse_entry(&entry)
+                    .map(|server_config| (server_id, server_config))
+            })
+            .collect())
+    }
+
+    fn render_servers(
+        &self,
+        text: &str,
+        servers: &HashMap<String, ClientServerConfig>,
+    ) -> Result<String> {
+        if self.descriptor.format == DescriptorFormat::Json && !text.trim().is_empty() {
+            // Plain JSON clients can't read comments, so refuse to build on a file that
+            // only parses leniently.
+            serde_json::from_str::<Value>(text)
+                .with_context(|| format!("{} config is not plain JSON", self.descriptor.name))?;
+        }
+        let servers_path = self.servers_path()?;
+        let servers_path: Vec<&str> = servers_path.iter().map(String::as_str).collect();
+        let mut entries = HashMap::new();
+        for (server_id, server_config) in servers {
+            entries.insert(server_id.clone(), self.to_entry(server_config)?);
+        }
+        jsonc_config::apply_entries(text.to_string(), &servers_path, &entries, |entry| {
+            self.parse_entry(entry)
+        })
+    }
+}
+
+/// Reads every descriptor in `data_dir/clients`. Broken files are logged and skipped
+/// so one typo doesn't hide the other clients.
+pub fn load_descriptors(data_dir: &Path) -> Vec<ClientDescriptor> {
+    let pattern = data_dir.join(DESCRIPTORS_DIR).join("*.json");
+    let Ok(paths) = glob::glob(&pattern.to_string_lossy()) else {
+        return vec![];
+    };
+    paths
+        .flatten()
+        .filter_map(|path| {
+            let descriptor = std::fs::read_to_string(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|text| Ok(serde_json::from_str::<ClientDescriptor>(&text)?))
+                .and_then(|descriptor| {
+                    parse_pointer(&descriptor.servers_pointer)?;
+                    Ok(descriptor)
+                });
+            match descriptor {
+                Ok(descriptor) => Some(descriptor),
+                Err(e) => {
+                    warn!(
+                        "Skipping client descriptor {}: {}",
+                        path.to_string_lossy(),
+                        e
+                    );
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Splits a JSON pointer into object keys, undoing `~1` and `~0` escapes.
+fn parse_pointer(pointer: &str) -> Result<Vec<String>> {
+    if pointer.is_empty() {
+        bail!("The servers pointer can't point at the document root");
+    }
+    let Some(rest) = pointer.strip_prefix('/') else {
+        bail!("Invalid JSON pointer `{}`", pointer);
+    };
+    Ok(rest
+        .split('/')
+        .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+        .collect())
+}
+
+fn env_var(name: &str) -> Result<String> {
+    if let Some(dir) = xdg_base_dir(name) {
+        return Ok(dir?.to_string_lossy().to_string());
+    }
+    std::env::var(name).with_context(|| format!("Environment variable {} is not set", name))
+}
+
+fn expand_path(raw_path: &str) -> Result<PathBuf> {
+    let mut expanded = String::new();
+    let mut rest = raw_path;
+    if let Some(stripped) = rest.strip_prefix('~') {
+        if stripped.is_empty() || stripped.starts_with(['/', '\\']) {
+            expanded.push_str(&get_home()?.to_string_lossy());
+            rest = stripped;
+        }
+    }
+
+    // `%` is an ordinary character in paths outside Windows.
+    let markers: &[char] = if cfg!(windows) { &['$', '%'] } else { &['$'] };
+    while let Some(start) = rest.find(markers) {
+        expanded.push_str(&rest[..start]);
+        let (name, after) = if rest[start..].starts_with("${") {
+            match rest[start + 2..].find('}') {
+                Some(end) => (&rest[start + 2..start + 2 + end], &rest[start + 3 + end..]),
+                None => bail!("Unterminated variable in `{}`", raw_path),
+            }
+        } else if let Some(end) = rest[start..].strip_prefix('%').and_then(|r| r.find('%')) {
+            (&rest[start + 1..start + 1 + end], &rest[start + 2 + end..])
+        } else {
+            expanded.push_str(&rest[start..start + 1]);
+            rest = &rest[start + 1..];
+            continue;
+        };
+        expanded.push_str(&env_var(name)?);
+        rest = after;
+    }
+    expanded.push_str(rest);
+    Ok(PathBuf::from(expanded))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn client(descriptor: Value) -> CustomClient {
+        CustomClient::new(serde_json::from_value(descriptor).unwrap())
+    }
+
+    #[test]
+    fn descriptors_fill_in_defaults() {
+        let client = client(json!({
+            "id": "acme",
+            "name": "Acme",
+            "configPath": { "linux": "~/.config/acme/config.json" },
+        }));
+        assert_eq!(client.servers_path().unwrap(), ["mcpServers"]);
+        assert_eq!(client.descriptor.format, DescriptorFormat::Json);
+        assert_eq!(client.descriptor.fields.command, "command");
+    }
+
+    #[test]
+    fn descriptor_fields_map_to_server_configs_and_back() {
+        let client = client(json!({
+            "id": "acme",
+            "name": "Acme",
+            "configPath": {},
+            "serversPointer": "/tools/mcp~1servers",
+            "fields": { "command": "cmd", "env": "environment" },
+            "format": "jsonc",
+        }));
+        assert_eq!(client.servers_path().unwrap(), ["tools", "mcp/servers"]);
+        let entry = json!({
+            "cmd": "npx",
+            "args": ["-y", "server"],
+            "environment": { "TOKEN": "t" },
+            "disabled": false,
+        });
+        let server_config = client.parse_entry(&entry).unwrap();
+        assert_eq!(server_config.command, "npx");
+        assert_eq!(server_config.args, ["-y", "server"]);
+        assert_eq!(server_config.env["TOKEN"], "t");
+        assert_eq!(client.to_entry(&server_config).unwrap(), entry);
+        assert!(client.parse_entry(&json!({ "command": "npx" })).is_none());
+    }
+
+    #[test]
+    fn broken_descriptors_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let descriptors = dir.path().join(DESCRIPTORS_DIR);
+        std::fs::create_dir(&descriptors).unwrap();
+        let good = json!({ "id": "good", "name": "Good", "configPath": {} });
+        let bad_pointer = json!({
+            "id": "bad",
+            "name": "Bad",
+            "configPath": {},
+            "serversPointer": "mcpServers",
+        });
+        std::fs::write(descriptors.join("good.json"), good.to_string()).unwrap();
+        std::fs::write(descriptors.join("bad.json"), bad_pointer.to_string()).unwrap();
+        std::fs::write(descriptors.join("typo.json"), "{ \"id\": ").unwrap();
+        let loaded = load_descriptors(dir.path());
+        let ids: Vec<&str> = loaded.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(ids, ["good"]);
+    }
+
+    #[test]
+    fn paths_expand_home_and_variables() {
+        let home = get_home().unwrap().to_string_lossy().to_string();
+        std::env::set_var("MCPHUB_TEST_ACME_DIR", "/opt/acme");
+        assert_eq!(
+            expand_path("~/acme/${MCPHUB_TEST_ACME_DIR}/$x/config.json").unwrap(),
+            PathBuf::from(format!("{}/acme//opt/acme/$x/config.json", home))
+        );
+        assert_eq!(
+            expand_path("~acme/config.json").unwrap(),
+            PathBuf::from("~acme/config.json")
+        );
+        assert!(expand_path("${MCPHUB_TEST_UNSET}/config.json").is_err());
+        assert!(expand_path("${MCPHUB_TEST_ACME_DIR/config.json").is_err());
+    }
+
+    #[test]
+    fn percent_signs_expand_only_on_windows() {
+        std::env::set_var("MCPHUB_TEST_PERCENT", "C:\\acme");
+        let expanded = expand_path("%MCPHUB_TEST_PERCENT%/100%.json").unwrap();
+        let expected = if cfg!(windows) {
+            "C:\\acme/100%.json"
+        } else {
+            "%MCPHUB_TEST_PERCENT%/100%.json"
+        };
+        assert_eq!(expanded, PathBuf::from(expected));
+    }
+
+    #[test]
+    fn xdg_variables_fall_back_when_unset_or_relative() {
+        let home = get_home().unwrap();
+        std::env::set_var("XDG_CACHE_HOME", "relative/cache");
+        assert_eq!(
+            expand_path("${XDG_CACHE_HOME}/acme").unwrap(),
+            home.join(".cache").join("acme")
+        );
+        std::env::set_var("XDG_CACHE_HOME", "/tmp/cache");
+        assert_eq!(
+            expand_path("${XDG_CACHE_HOME}/acme").unwrap(),
+            PathBuf::from("/tmp/cache/acme")
+        );
+        std::env::remove_var("XDG_CACHE_HOME");
+        assert_eq!(
+            expand_path("${XDG_CACHE_HOME}/acme").unwrap(),
+            home.join(".cache").join("acme")
+        );
+    }
+}
@@ -0,0 +1,68 @@
+//! Shared plumbing for clients whose server map lives somewhere inside a JSONC file.
+
+use super::ClientServerConfig;
+use crate::utils::jsonc;
+use anyhow::Result;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// The raw server entries found at `servers_path`.
+pub fn entries(text: &str, servers_path: &[&str]) -> Result<Map<String, Value>> {
+    let document = jsonc::parse(text)?;
+    Ok(jsonc::get(&document, servers_path)
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default())
+}
+
+/// Makes the map at `servers_path` match `wanted`, touching only entries that changed.
+/// Existing entries `parse` can't read are kept even when absent from `wanted`, since
+/// the caller could not have read them in the first place.
+pub fn apply_entries(
+    mut text: String,
+    servers_path: &[&str],
+    wanted: &HashMap<String, Value>,
+    parse: impl Fn(&Value) -> Option<ClientServerConfig>,
+) -> Result<String> {
+    let existing = entries(&text, servers_path)?;
+    for (server_id, entry) in &existing {
+        if parse(entry).is_some() && !wanted.contains_key(server_id) {
+            text = jsonc::remove_value(&text, &entry_path(servers_path, server_id))?;
+        }
+    }
+    for (server_id, entry) in wanted {
+        if let Some(current) = existing.get(server_id) {
+            if same_entry(current, entry, &parse) {
+                continue;
+            }
+        }
+        text = jsonc::set_value(&text, &entry_path(servers_path, server_id), entry)?;
+    }
+    Ok(text)
+}
+
+/// Whether entry `current` already says what `wanted` does as far as `parse` reads
+/// them. Spelling differences, such as an empty `args` left out, don't count, so an
+/// entry nobody changed keeps its comments and formatting.
+pub fn same_entry(
+    current: &Value,
+    wanted: &Value,
+    parse: impl Fn(&Value) -> Option<ClientServerConfig>,
+) -> bool {
+    if current == wanted {
+        return true;
+    }
+    let (Some(current), Some(wanted)) = (parse(current), parse(wanted)) else {
+        return false;
+    };
+    match (serde_json::to_value(current), serde_json::to_value(wanted)) {
+        (Ok(current), Ok(wanted)) => current == wanted,
+        _ => false,
+    }
+}
+
+fn entry_path<'a>(servers_path: &[&'a str], server_id: &'a str) -> Vec<&'a str> {
+    let mut path = servers_path.to_vec();
+    path.push(server_id);
+    path
+}
@@ -0,0 +1,674 @@
+pub mod claude_code;
+pub mod claude_desktop;
+pub mod codex;
+pub mod cursor;
+pub mod custom;
+mod jsonc_config;
+pub mod vscode;
+pub mod windsurf;
+pub mod zed;
+
+use crate::api::backups::core::create_backup;
+use crate::api::servers::core::{deserialize_input_args, InputArg};
+use crate::api::watcher::core::{note_own_write, notify_conflict};
+use crate::error::AppError;
+use crate::utils::fs::{lock_file, path_key, write_atomic};
+use crate::utils::jsonc;
+use crate::utils::process::find_processes;
+use anyhow::{anyhow, Context, Result};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use tauri::Manager;
+
+pub const DEFAULT_CLIENT_ID: &str = claude_desktop::CLIENT_ID;
+
+/// `commandCreator` value marking entries that MCPHub wrote.
+pub const MCPHUB_CREATOR: &str = "MCPHub";
+
+/// Which of a client's config files an operation targets.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(tag = "type", content = "path", rename_all = "lowercase")]
+pub enum ConfigScope {
+    /// The per-user config file in the home directory.
+    #[default]
+    User,
+    /// A config file checked into the given workspace directory.
+    Project(PathBuf),
+    /// Per-user settings that only apply inside the given workspace directory.
+    Local(PathBuf),
+}
+
+impl ConfigScope {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ConfigScope::User => "user",
+            ConfigScope::Project(_) => "project",
+            ConfigScope::Local(_) => "local",
+        }
+    }
+
+    /// Whether the config file is meant to be committed and shared with others.
+    pub fn is_shared(&self) -> bool {
+        matches!(self, ConfigScope::Project(_))
+    }
+}
+
+/// A client plus the scope of its config that a server command operates on.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ClientTarget {
+    #[serde(rename = "clientId", default)]
+    pub client_id: Option<String>,
+    #[serde(default)]
+    pub scope: ConfigScope,
+}
+
+impl ClientTarget {
+    pub fn new(client_id: Option<String>, scope: Option<ConfigScope>) -> Self {
+        ClientTarget {
+            client_id,
+            scope: scope.unwrap_or_default(),
+        }
+    }
+
+    pub fn client_id(&self) -> &str {
+        self.client_id.as_deref().unwrap_or(DEFAULT_CLIENT_ID)
+    }
+}
+
+/// A server entry as MCPHub understands it. Only `command` or `url` is needed, so
+/// entries written by hand or by other tools load as well; their extra keys are kept
+/// in `other_fields` and written back unchanged.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClientServerConfig {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env: HashMap<String, String>,
+    #[serde(
+        rename = "commandCreator",
+        default,
+        skip_serializing_if = "String::is_empty"
+    )]
+    pub command_creator: String,
+    #[serde(
+        rename = "inputArgs",
+        alias = "inputArg",
+        default,
+        deserialize_with = "deserialize_input_args",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub input_args: Vec<InputArg>,
+    /// Catalog server this entry is an instance of, when its key isn't the catalog id.
+    #[serde(rename = "catalogId", default, skip_serializing_if = "Option::is_none")]
+    pub catalog_id: Option<String>,
+    /// Endpoint of a remote server; such entries have no command.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(flatten, default)]
+    pub other_fields: HashMap<String, Value>,
+}
+
+impl ClientServerConfig {
+    /// Reads a raw config entry, returning `None` for anything that isn't a server,
+    /// i.e. has neither a command nor a URL.
+    pub fn from_entry(entry: &Value) -> Option<Self> {
+        let server_config: ClientServerConfig = serde_json::from_value(entry.clone()).ok()?;
+        if server_config.command.is_empty() && server_config.url.is_none() {
+            return None;
+        }
+        Some(server_config)
+    }
+}
+
+/// On-disk shape shared by clients that keep their servers in a top level
+/// `mcpServers` map. Unknown keys are carried through untouched, and so are entries
+/// that aren't server configs at all.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ClientConfig {
+    #[serde(rename = "mcpServers", default)]
+    pub mcp_servers: Map<String, Value>,
+    #[serde(flatten, default)]
+    pub other_fields: HashMap<String, Value>,
+}
+
+impl ClientConfig {
+    /// Parses the file leniently: comments and trailing commas are accepted, and are
+    /// kept when the file is written.
+    pub fn parse(text: &str) -> Result<Self> {
+        if text.trim().is_empty() {
+            debug!("Config file empty or missing, returning empty HashMap");
+            return Ok(ClientConfig::default());
+        }
+        let config: ClientConfig = serde_json::from_value(jsonc::parse(text)?)?;
+        debug!("ClientConfig parsed config");
+        Ok(config)
+    }
+
+    pub fn parse_servers(text: &str) -> Result<HashMap<String, ClientServerConfig>> {
+        Ok(Self::parse(text)?
+            .mcp_servers
+            .into_iter()
+            .filter_map(|(server_id, entry)| {
+                ClientServerConfig::from_entry(&entry)
+                    .map(|server_config| (server_id, server_config))
+            })
+            .collect())
+    }
+
+    /// Writes `servers` into the `mcpServers` map of `text`, leaving comments, key order
+    /// and formatting of everything else as they are.
+    pub fn render_servers(
+        text: &str,
+        servers: &HashMap<String, ClientServerConfig>,
+    ) -> Result<String> {
+        let mut entries = HashMap::new();
+        for (server_id, server_config) in servers {
+            entries.insert(server_id.clone(), serde_json::to_value(server_config)?);
+        }
+        jsonc_config::apply_entries(
+            text.to_string(),
+            &["mcpServers"],
+            &entries,
+            ClientServerConfig::from_entry,
+        )
+    }
+}
+
+/// A client config file that could not be parsed. MCPHub never writes such a file,
+/// so the user's version stays as it is until they fix it.
+#[derive(Debug)]
+pub struct ConfigParseError {
+    pub path: PathBuf,
+    pub message: String,
+    /// 1-based position of the error, when the parser reported one.
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+impl ConfigParseError {
+    fn new(config_path: &Path, text: &str, error: &anyhow::Error) -> Self {
+        let mut parse_error = ConfigParseError {
+            path: config_path.to_path_buf(),
+            message: error.to_string(),
+            line: None,
+            column: None,
+        };
+        if let Some(e) = error.downcast_ref::<serde_json::Error>() {
+            if e.line() > 0 {
+                let message = e.to_string();
+                let suffix = format!(" at line {} column {}", e.line(), e.column());
+                parse_error.message = message.trim_end_matches(&suffix).to_string();
+                parse_error.line = Some(e.line());
+                parse_error.column = Some(e.column().max(1));
+            }
+        } else if let Some(e) = error.downcast_ref::<toml_edit::TomlError>() {
+            parse_error.message = e.message().trim().replace('\n', "; ");
+            if let Some(span) = e.span() {
+                let before = &text[..span.start.min(text.len())];
+                parse_error.line = Some(before.matches('\n').count() + 1);
+                parse_error.column =
+                    Some(before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1);
+            }
+        }
+        parse_error
+    }
+}
+
+impl std::fmt::Display for ConfigParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Failed to parse {}", self.path.to_string_lossy())?;
+        if let (Some(line), Some(column)) = (self.line, self.column) {
+            write!(f, " at line {}, column {}", line, column)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+impl std::error::Error for ConfigParseError {}
+
+/// An application that can launch MCP servers from a config file MCPHub is able to edit.
+///
+/// Clients only translate between file contents and server entries; reading and
+/// writing the file itself goes through [`read_servers`] and [`update_servers`] so
+/// every client gets the same atomic, backed-up writes.
+pub trait McpClient: Send + Sync {
+    /// Stable identifier the frontend uses to address this client.
+    fn id(&self) -> &str;
+
+    /// Name shown in the UI.
+    fn name(&self) -> &str;
+
+    /// Scope kinds this client can be targeted with, see [`ConfigScope::kind`].
+    fn scopes(&self) -> &'static [&'static str] {
+        &["user"]
+    }
+
+    /// Location of the config file holding this client's server entries for the
+    /// scope the client was created with.
+    fn config_path(&self) -> Result<PathBuf>;
+
+    /// Whether the client looks installed on this machine. The default checks
+    /// that the directory holding the config file exists.
+    fn is_installed(&self) -> bool {
+        self.config_path()
+            .ok()
+            .and_then(|path| path.parent().map(|dir| dir.exists()))
+            .unwrap_or(false)
+    }
+
+    /// Executable names of the client app, for clients that only read their config
+    /// at startup. Empty for clients that pick up changes by themselves.
+    fn process_names(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// What a shared config gets in place of the value of secret env variable `key` of
+    /// server `server_id`: a reference the client resolves when it starts the server.
+    /// The default works for clients that expand `${VAR}` from their environment.
+    fn secret_reference(&self, _server_id: &str, key: &str) -> String {
+        format!("${{{}}}", key)
+    }
+
+    /// Extracts the server entries from the config file contents. An empty `text`
+    /// stands for a missing file.
+    fn parse_servers(&self, text: &str) -> Result<HashMap<String, ClientServerConfig>>;
+
+    /// Returns `text` with the server entries replaced by `servers`, leaving the rest
+    /// of the config untouched.
+    fn render_servers(
+        &self,
+        text: &str,
+        servers: &HashMap<String, ClientServerConfig>,
+    ) -> Result<String>;
+}
+
+/// Whether the client app is running and has to be restarted to see config changes.
+pub fn needs_restart(client: &dyn McpClient) -> bool {
+    !find_processes(client.process_names()).is_empty()
+}
+
+/// Reads a client config file, treating a missing file as empty.
+pub fn read_config_file(config_path: &Path) -> Result<String> {
+    match std::fs::read_to_string(config_path) {
+        Ok(text) => Ok(text),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => {
+            Err(e).with_context(|| format!("Failed to read {}", config_path.to_string_lossy()))
+        }
+    }
+}
+
+/// Reads every server entry currently configured for `client`.
+pub fn read_servers(client: &dyn McpClient) -> Result<HashMap<String, ClientServerConfig>> {
+    let config_path = client.config_path()?;
+    let text = read_config_file(&config_path)?;
+    client
+        .parse_servers(&text)
+        .map_err(|e| ConfigParseError::new(&config_path, &text, &e).into())
+}
+
+/// Backs up the current contents of `config_path`, then atomically replaces them.
+pub fn write_config_file(
+    app_handle: &tauri::AppHandle,
+    config_path: &Path,
+    contents: &str,
+) -> Result<()> {
+    create_backup(app_handle, config_path)?;
+    // Announce the write before the file changes so the watcher never sees it as foreign.
+    note_own_write(app_handle, config_path, contents);
+    write_atomic(config_path, contents.as_bytes())
+        .with_context(|| format!("Failed to write {}", config_path.to_string_lossy()))
+}
+
+/// Serializes MCPHub's read-modify-write cycles on client configs and on the state
+/// kept next to them, such as history and parked servers. Managed in app state.
+#[derive(Default)]
+pub struct ConfigMutations(Mutex<()>);
+
+/// Waits until no other command in this process is changing a config. Mutating
+/// commands hold the guard from their first read to their last write.
+pub fn lock_mutations(app_handle: &tauri::AppHandle) -> MutexGuard<'_, ()> {
+    app_handle
+        .state::<ConfigMutations>()
+        .inner()
+        .0
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Directory inside the MCPHub data dir that config lock files are kept in.
+const LOCKS_DIR: &str = "locks";
+
+/// Takes the advisory lock other MCPHub processes check before writing `config_path`.
+pub fn lock_config_file(app_handle: &tauri::AppHandle, config_path: &Path) -> Result<File> {
+    let lock_path = app_handle
+        .path()
+        .app_data_dir()?
+        .join(LOCKS_DIR)
+        .join(format!("{}.lock", path_key(config_path)));
+    lock_file(&lock_path)
+}
+
+/// Applies `change` to the server entries of `client` and writes the result. Returns
+/// the entries as they were before and after the change.
+///
+/// If the file is edited by someone else while the change is being prepared, nothing
+/// is written and a [`ConfigConflictError`] is returned instead of overwriting their edit.
+pub fn update_servers(
+    app_handle: &tauri::AppHandle,
+    client: &dyn McpClient,
+    change: impl FnOnce(&mut HashMap<String, ClientServerConfig>) -> Result<()>,
+) -> Result<ServersChange> {
+    let _lock = lock_config_file(app_handle, &client.config_path()?)?;
+    let (config_path, text, servers, updated) = prepare_servers(client, change)?;
+    if updated != text {
+        if let Some(conflict) = find_conflict(&config_path, &text)? {
+            notify_conflict(app_handle, &conflict);
+            return Err(conflict.into());
+        }
+        write_config_file(app_handle, &config_path, &updated)?;
+    }
+    Ok(servers)
+}
+
+/// The conflict to report when the file at `config_path` no longer holds `text`, the
+/// contents a change was prepared from.
+fn find_conflict(config_path: &Path, text: &str) -> Result<Option<ConfigConflictError>> {
+    let changed = read_config_file(config_path)? != text;
+    Ok(changed.then(|| ConfigConflictError {
+        path: config_path.to_path_buf(),
+    }))
+}
+
+/// Works out what [`update_servers`] would write without touching the file.
+pub fn preview_servers(
+    client: &dyn McpClient,
+    change: impl FnOnce(&mut HashMap<String, ClientServerConfig>) -> Result<()>,
+) -> Result<ServersChange> {
+    let (_, _, servers, _) = prepare_servers(client, change)?;
+    Ok(servers)
+}
+
+/// Reads the config of `client` and renders it with `change` applied. Returns the
+/// path, the current text, the entries around the change and the new text.
+fn prepare_servers(
+    client: &dyn McpClient,
+    change: impl FnOnce(&mut HashMap<String, ClientServerConfig>) -> Result<()>,
+) -> Result<(PathBuf, String, ServersChange, String)> {
+    let config_path = client.config_path()?;
+    let text = read_config_file(&config_path)?;
+    // Refuse to build on a file we can't read; rewriting it would lose the user's data.
+    let before = client
+        .parse_servers(&text)
+        .map_err(|e| ConfigParseError::new(&config_path, &text, &e))?;
+    let mut after = before.clone();
+    change(&mut after)?;
+    let updated = client
+        .render_servers(&text, &after)
+        .with_context(|| format!("Failed to update {}", config_path.to_string_lossy()))?;
+    Ok((config_path, text, ServersChange { before, after }, updated))
+}
+
+/// Another program changed a config file while MCPHub was about to write it.
+#[derive(Debug)]
+pub struct ConfigConflictError {
+    pub path: PathBuf,
+}
+
+impl std::fmt::Display for ConfigConflictError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} was changed by another program; reload and try again",
+            self.path.to_string_lossy()
+        )
+    }
+}
+
+impl std::error::Error for ConfigConflictError {}
+
+/// Server entries of one config file around a write.
+pub struct ServersChange {
+    pub before: HashMap<String, ClientServerConfig>,
+    pub after: HashMap<String, ClientServerConfig>,
+}
+
+const CLIENT_IDS: &[&str] = &[
+    claude_desktop::CLIENT_ID,
+    claude_code::CLIENT_ID,
+    cursor::CLIENT_ID,
+    windsurf::CLIENT_ID,
+    vscode::CLIENT_ID,
+    zed::CLIENT_ID,
+    codex::CLIENT_ID,
+];
+
+fn create_builtin_client(client_id: &str, scope: &ConfigScope) -> Option<Box<dyn McpClient>> {
+    let client: Box<dyn McpClient> = match client_id {
+        claude_desktop::CLIENT_ID => Box::new(claude_desktop::ClaudeDesktopClient),
+        claude_code::CLIENT_ID => Box::new(claude_code::ClaudeCodeClient::new(scope.clone())),
+        cursor::CLIENT_ID => Box::new(cursor::CursorClient::new(scope.clone())),
+        windsurf::CLIENT_ID => Box::new(windsurf::WindsurfClient),
+        vscode::CLIENT_ID => Box::new(vscode::VsCodeClient::new(scope.clone())),
+        zed::CLIENT_ID => Box::new(zed::ZedClient),
+        codex::CLIENT_ID => Box::new(codex::CodexClient),
+        _ => return None,
+    };
+    Some(client)
+}
+
+/// Clients described by files in the MCPHub data dir. Descriptors are re-read on
+/// every call so new files show up without restarting.
+fn custom_clients(app_handle: &tauri::AppHandle) -> Vec<Box<dyn McpClient>> {
+    let Ok(data_dir) = app_handle.path().app_data_dir() else {
+        return vec![];
+    };
+    custom::load_descriptors(&data_dir)
+        .into_iter()
+        .map(|descriptor| Box::new(custom::CustomClient::new(descriptor)) as Box<dyn McpClient>)
+        .filter(|client| {
+            let shadowed = CLIENT_IDS.contains(&client.id());
+            if shadowed {
+                warn!(
+                    "Ignoring custom client {}: id is taken by a built-in client",
+                    client.id()
+                );
+            }
+            !shadowed
+        })
+        .collect()
+}
+
+fn create_client(
+    app_handle: &tauri::AppHandle,
+    client_id: &str,
+    scope: &ConfigScope,
+) -> Result<Box<dyn McpClient>> {
+    let client = match create_builtin_client(client_id, scope) {
+        Some(client) => client,
+        None => custom_clients(app_handle)
+            .into_iter()
+            .find(|client| client.id() == client_id)
+            .ok_or_else(|| AppError::ClientNotFound {
+                client_id: client_id.to_string(),
+            })?,
+    };
+    if !client.scopes().contains(&scope.kind()) {
+        return Err(anyhow!(
+            "{} does not support the {} scope",
+            client.name(),
+            scope.kind()
+        ));
+    }
+    Ok(client)
+}
+
+/// Every client MCPHub knows how to manage, at user scope, built-in ones first.
+pub fn all_clients(app_handle: &tauri::AppHandle) -> Vec<Box<dyn McpClient>> {
+    CLIENT_IDS
+        .iter()
+        .filter_map(|client_id| create_builtin_client(client_id, &ConfigScope::User))
+        .chain(custom_clients(app_handle))
+        .collect()
+}
+
+/// Looks up a registered client bound to the target's scope, falling back to
+/// Claude Desktop when the target has no client id.
+pub fn get_client(
+    app_handle: &tauri::AppHandle,
+    target: &ClientTarget,
+) -> Result<Box<dyn McpClient>> {
+    create_client(app_handle, target.client_id(), &target.scope)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A client with a plain `mcpServers` config at `path`.
+    struct TestClient {
+        path: PathBuf,
+    }
+
+    impl McpClient for TestClient {
+        fn id(&self) -> &str {
+            "test"
+        }
+
+        fn name(&self) -> &str {
+            "Test"
+        }
+
+        fn config_path(&self) -> Result<PathBuf> {
+            Ok(self.path.clone())
+        }
+
+        fn parse_servers(&self, text: &str) -> Result<HashMap<String, ClientServerConfig>> {
+            ClientConfig::parse_servers(text)
+        }
+
+        fn render_servers(
+            &self,
+            text: &str,
+            servers: &HashMap<String, ClientServerConfig>,
+        ) -> Result<String> {
+            ClientConfig::render_servers(text, servers)
+        }
+    }
+
+    fn server(command: &str) -> ClientServerConfig {
+        serde_json::from_value(serde_json::json!({ "command": command })).unwrap()
+    }
+
+    #[test]
+    fn edits_made_while_a_change_is_prepared_are_conflicts() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = TestClient {
+            path: dir.path().join("config.json"),
+        };
+        let original = r#"{ "mcpServers": { "fetch": { "command": "uvx" } } }"#;
+        let edited = r#"{ "mcpServers": { "time": { "command": "uvx" } } }"#;
+        std::fs::write(&client.path, original).unwrap();
+
+        let (config_path, text, _, updated) = prepare_servers(&client, |servers| {
+            std::fs::write(&client.path, edited)?;
+            servers.insert("github".to_string(), server("npx"));
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(text, original);
+        assert!(updated.contains("github"));
+        let conflict = find_conflict(&config_path, &text).unwrap().unwrap();
+        assert_eq!(conflict.path, client.path);
+        assert_eq!(std::fs::read_to_string(&client.path).unwrap(), edited);
+    }
+
+    #[test]
+    fn unchanged_files_are_not_conflicts() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = TestClient {
+            path: dir.path().join("config.json"),
+        };
+        // A missing file reads as empty, and is still missing.
+        let (config_path, text, _, _) = prepare_servers(&client, |servers| {
+            servers.insert("github".to_string(), server("npx"));
+            Ok(())
+        })
+        .unwrap();
+        assert!(find_conflict(&config_path, &text).unwrap().is_none());
+
+        std::fs::write(&client.path, r#"{ "mcpServers": {} }"#).unwrap();
+        let (config_path, text, _, _) = prepare_servers(&client, |_| Ok(())).unwrap();
+        assert!(find_conflict(&config_path, &text).unwrap().is_none());
+    }
+
+    #[test]
+    fn rendering_servers_keeps_comments_and_key_order() {
+        let text = r#"{
+  // Set by the installer
+  "globalShortcut": "",
+  "mcpServers": {
+    "zeta": { "command": "python", "args": ["z.py"] }, // mine
+    "old": { "command": "npx", "commandCreator": "MCPHub" },
+  },
+  "alpha": true
+}
+"#;
+        let mut servers = ClientConfig::parse_servers(text).unwrap();
+        servers.remove("old");
+        servers.insert(
+            "new".to_string(),
+            ClientServerConfig {
+                command: "uvx".to_string(),
+                args: vec![],
+                env: HashMap::new(),
+                command_creator: MCPHUB_CREATOR.to_string(),
+                input_args: vec![],
+                catalog_id: None,
+                url: None,
+                other_fields: HashMap::new(),
+            },
+        );
+        let rendered = ClientConfig::render_servers(text, &servers).unwrap();
+        assert_eq!(
+            rendered,
+            r#"{
+  // Set by the installer
+  "globalShortcut": "",
+  "mcpServers": {
+    "zeta": { "command": "python", "args": ["z.py"] }, // mine
+    "new": {
+      "command": "uvx",
+      "commandCreator": "MCPHub"
+    },
+  },
+  "alpha": true
+}
+"#
+        );
+    }
+
+    #[test]
+    fn rendering_servers_leaves_equivalent_entries_alone() {
+        let text = r#"{
+  "mcpServers": {
+    "mine": {
+      "command": "python", // local checkout
+      "args": [],
+      "env": {}
+    }
+  }
+}
+"#;
+        let servers = ClientConfig::parse_servers(text).unwrap();
+        let rendered = ClientConfig::render_servers(text, &servers).unwrap();
+        assert_eq!(rendered, text);
+    }
+}
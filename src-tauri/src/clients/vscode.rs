@@ -0,0 +1,212 @@
+use super::{jsonc_config, ClientServerConfig, ConfigScope, McpClient};
+use crate::utils::jsonc;
+use crate::utils::os::get_config_dir;
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+pub const CLIENT_ID: &str = "vscode";
+
+/// VS Code keeps servers in `mcp.servers` of the user `settings.json`, or under a top
+/// level `servers` key in a workspace's `.vscode/mcp.json`. Both files are JSONC, so
+/// all edits go through [`jsonc`] to keep the user's comments and layout.
+pub struct VsCodeClient {
+    scope: ConfigScope,
+}
+
+impl VsCodeClient {
+    pub fn new(scope: ConfigScope) -> Self {
+        VsCodeClient { scope }
+    }
+
+    fn servers_path(&self) -> &'static [&'static str] {
+        match self.scope {
+            ConfigScope::Project(_) => &["servers"],
+            _ => &["mcp", "servers"],
+        }
+    }
+
+    fn inputs_path(&self) -> &'static [&'static str] {
+        match self.scope {
+            ConfigScope::Project(_) => &["inputs"],
+            _ => &["mcp", "inputs"],
+        }
+    }
+
+    /// Drops prompts only the touched entries referenced and adds the ones they now need.
+    fn sync_inputs(
+        &self,
+        text: String,
+        touched: &HashSet<String>,
+        wanted: Vec<Value>,
+    ) -> Result<String> {
+        let document = jsonc::parse(&text)?;
+        let current = jsonc::get(&document, self.inputs_path()).and_then(Value::as_array);
+        let referenced: HashSet<String> = jsonc::get(&document, self.servers_path())
+            .map(input_references)
+            .unwrap_or_default();
+
+        let mut inputs: Vec<Value> = current
+            .into_iter()
+            .flatten()
+            .filter(|input| match input.get("id").and_then(Value::as_str) {
+                Some(id) => !touched.contains(id) || referenced.contains(id),
+                None => true,
+            })
+            .cloned()
+            .collect();
+        for input in wanted {
+            if !inputs
+                .iter()
+                .any(|existing| existing.get("id") == input.get("id"))
+            {
+                inputs.push(input);
+            }
+        }
+
+        let unchanged = match current {
+            Some(current) => *current == inputs,
+            None => inputs.is_empty(),
+        };
+        if unchanged {
+            return Ok(text);
+        }
+        jsonc::set_value(&text, self.inputs_path(), &Value::Array(inputs))
+    }
+}
+
+impl McpClient for VsCodeClient {
+    fn id(&self) -> &str {
+        CLIENT_ID
+    }
+
+    fn name(&self) -> &str {
+        "VS Code"
+    }
+
+    fn scopes(&self) -> &'static [&'static str] {
+        &["user", "project"]
+    }
+
+    fn is_installed(&self) -> bool {
+        get_config_dir()
+            .map(|dir| dir.join("Code").join("User").exists())
+            .unwrap_or(false)
+    }
+
+    fn config_path(&self) -> Result<PathBuf> {
+        match &self.scope {
+            ConfigScope::Project(workspace) => Ok(workspace.join(".vscode").join("mcp.json")),
+            _ => Ok(get_config_dir()?
+                .join("Code")
+                .join("User")
+                .join("settings.json")),
+        }
+    }
+
+    /// VS Code prompts for the value and keeps it in its own secret storage.
+    fn secret_reference(&self, server_id: &str, key: &str) -> String {
+        input_reference(server_id, key)
+    }
+
+    fn parse_servers(&self, text: &str) -> Result<HashMap<String, ClientServerConfig>> {
+        // Entries that aren't servers MCPHub can read are left out here and never
+        // touched by `render_servers`.
+        Ok(jsonc_config::entries(text, self.servers_path())?
+            .into_iter()
+            .filter_map(|(server_id, entry)| {
+                ClientServerConfig::from_entry(&entry)
+                    .map(|server_config| (server_id, server_config))
+            })
+            .collect())
+    }
+
+    fn render_servers(
+        &self,
+        text: &str,
+        servers: &HashMap<String, ClientServerConfig>,
+    ) -> Result<String> {
+        let existing = jsonc_config::entries(text, self.servers_path())?;
+        let mut wanted_inputs = Vec::new();
+        let mut entries = HashMap::new();
+        for (server_id, server_config) in servers {
+            let entry = to_vscode_entry(server_id, server_config, &mut wanted_inputs)?;
+            entries.insert(server_id.clone(), entry);
+        }
+
+        // Prompts referenced by entries that are rewritten or removed may become unused.
+        let touched_inputs: HashSet<String> = existing
+            .iter()
+            .filter(|(server_id, entry)| {
+                is_known_entry(entry)
+                    && !entries.get(*server_id).is_some_and(|wanted| {
+                        jsonc_config::same_entry(entry, wanted, ClientServerConfig::from_entry)
+                    })
+            })
+            .flat_map(|(_, entry)| input_references(entry))
+            .collect();
+
+        let text = jsonc_config::apply_entries(
+            text.to_string(),
+            self.servers_path(),
+            &entries,
+            ClientServerConfig::from_entry,
+        )?;
+        self.sync_inputs(text, &touched_inputs, wanted_inputs)
+    }
+}
+
+fn is_known_entry(entry: &Value) -> bool {
+    ClientServerConfig::from_entry(entry).is_some()
+}
+
+/// Builds a VS Code entry, `stdio` unless it points at a URL. Env values left empty become `${input:...}`
+/// references so VS Code prompts for them on first start instead of storing them, and
+/// each such reference gets its prompt.
+fn to_vscode_entry(
+    server_id: &str,
+    server_config: &ClientServerConfig,
+    wanted_inputs: &mut Vec<Value>,
+) -> Result<Value> {
+    let mut server_config = server_config.clone();
+    for (key, value) in server_config.env.iter_mut() {
+        let reference = input_reference(server_id, key);
+        if value.is_empty() || *value == reference {
+            *value = reference;
+            wanted_inputs.push(json!({
+                "type": "promptString",
+                "id": format!("{}-{}", server_id, key),
+                "description": format!("{} for {}", key, server_id),
+                "password": true,
+            }));
+        }
+    }
+
+    let mut entry = serde_json::to_value(&server_config)?;
+    if let Some(object) = entry.as_object_mut() {
+        let transport = if server_config.url.is_some() {
+            "http"
+        } else {
+            "stdio"
+        };
+        object.entry("type").or_insert(json!(transport));
+    }
+    Ok(entry)
+}
+
+/// The `${input:...}` reference to the prompt for env variable `key` of `server_id`.
+fn input_reference(server_id: &str, key: &str) -> String {
+    format!("${{input:{}-{}}}", server_id, key)
+}
+
+/// Ids of every `${input:...}` prompt referenced inside `value`.
+fn input_references(value: &Value) -> HashSet<String> {
+    let text = value.to_string();
+    text.match_indices("${input:")
+        .filter_map(|(start, marker)| {
+            let rest = &text[start + marker.len()..];
+            rest.find('}').map(|end| rest[..end].to_string())
+        })
+        .collect()
+}
@@ -0,0 +1,53 @@
+use super::{ClientConfig, ClientServerConfig, McpClient};
+use crate::utils::os::get_home;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+pub const CLIENT_ID: &str = "windsurf";
+
+pub struct WindsurfClient;
+
+impl McpClient for WindsurfClient {
+    fn id(&self) -> &str {
+        CLIENT_ID
+    }
+
+    fn name(&self) -> &str {
+        "Windsurf"
+    }
+
+    fn config_path(&self) -> Result<PathBuf> {
+        Ok(get_home()?
+            .join(".codeium")
+            .join("windsurf")
+            .join("mcp_config.json"))
+    }
+
+    fn process_names(&self) -> &'static [&'static str] {
+        #[cfg(target_os = "macos")]
+        {
+            &["Windsurf"]
+        }
+        #[cfg(target_os = "windows")]
+        {
+            &["Windsurf.exe"]
+        }
+        #[cfg(target_os = "linux")]
+        {
+            &["windsurf"]
+        }
+    }
+
+    fn parse_servers(&self, text: &str) -> Result<HashMap<String, ClientServerConfig>> {
+        ClientConfig::parse_servers(text)
+    }
+
+    fn render_servers(
+        &self,
+        text: &str,
+        servers: &HashMap<String, ClientServerConfig>,
+    ) -> Result<String> {
+        ClientConfig::render_servers(text, servers)
+    }
+}
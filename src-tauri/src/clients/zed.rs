@@ -0,0 +1,88 @@
+use super::{jsonc_config, ClientServerConfig, McpClient};
+#[cfg(not(target_os = "macos"))]
+use crate::utils::os::get_config_dir;
+#[cfg(target_os = "macos")]
+use crate::utils::os::get_home;
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+pub const CLIENT_ID: &str = "zed";
+
+const SERVERS_PATH: &[&str] = &["context_servers"];
+
+/// Zed keeps servers under `context_servers` in its JSONC `settings.json`. Entries are
+/// written in the flat `"source": "custom"` shape; the older shape that nests
+/// `path`/`args`/`env` under `command` is still understood when reading.
+pub struct ZedClient;
+
+impl McpClient for ZedClient {
+    fn id(&self) -> &str {
+        CLIENT_ID
+    }
+
+    fn name(&self) -> &str {
+        "Zed"
+    }
+
+    fn config_path(&self) -> Result<PathBuf> {
+        // Zed uses ~/.config on macOS too rather than Application Support.
+        #[cfg(target_os = "macos")]
+        let config_dir = get_home()?.join(".config").join("zed");
+        #[cfg(target_os = "windows")]
+        let config_dir = get_config_dir()?.join("Zed");
+        #[cfg(target_os = "linux")]
+        let config_dir = get_config_dir()?.join("zed");
+
+        Ok(config_dir.join("settings.json"))
+    }
+
+    fn parse_servers(&self, text: &str) -> Result<HashMap<String, ClientServerConfig>> {
+        Ok(jsonc_config::entries(text, SERVERS_PATH)?
+            .into_iter()
+            .filter_map(|(server_id, entry)| {
+                from_zed_entry(&entry).map(|server_config| (server_id, server_config))
+            })
+            .collect())
+    }
+
+    fn render_servers(
+        &self,
+        text: &str,
+        servers: &HashMap<String, ClientServerConfig>,
+    ) -> Result<String> {
+        let mut entries = HashMap::new();
+        for (server_id, server_config) in servers {
+            entries.insert(server_id.clone(), to_zed_entry(server_config)?);
+        }
+        jsonc_config::apply_entries(text.to_string(), SERVERS_PATH, &entries, from_zed_entry)
+    }
+}
+
+fn to_zed_entry(server_config: &ClientServerConfig) -> Result<Value> {
+    let mut entry = serde_json::to_value(server_config)?;
+    if let Some(object) = entry.as_object_mut() {
+        // Zed insists on `args` even when there are none.
+        object.entry("args").or_insert(json!([]));
+        object.entry("source").or_insert(json!("custom"));
+    }
+    Ok(entry)
+}
+
+/// Maps either entry shape back to a [`ClientServerConfig`]. Extension-provided
+/// servers have no command and yield `None`.
+fn from_zed_entry(entry: &Value) -> Option<ClientServerConfig> {
+    let mut entry = entry.clone();
+    if let Some(Value::Object(command)) = entry.get("command").cloned() {
+        let object = entry.as_object_mut()?;
+        object.insert("command".to_string(), command.get("path")?.clone());
+        for key in ["args", "env"] {
+            if let Some(value) = command.get(key) {
+                object.insert(key.to_string(), value.clone());
+            }
+        }
+    }
+    entry.get("command")?.as_str()?;
+    ClientServerConfig::from_entry(&entry)
+}
@@ -0,0 +1,395 @@
+use crate::clients::{ConfigConflictError, ConfigParseError};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use serde_json::{json, Value};
+
+/// Error returned by Tauri commands. It reaches the UI as
+/// `{ code, message, context }`, where `code` names the variant in camelCase and
+/// `context` carries its fields, so the UI can react to specific failures.
+#[derive(Debug)]
+pub enum AppError {
+    /// The server catalog has not been downloaded yet.
+    CatalogMissing,
+    /// No catalog server has this id.
+    ServerNotFound {
+        server_id: String,
+    },
+    /// The client config has no entry with this id.
+    ServerNotInstalled {
+        server_id: String,
+    },
+    /// No built-in or custom client has this id.
+    ClientNotFound {
+        client_id: String,
+    },
+    /// A client config could not be parsed; see [`ConfigParseError`].
+    ConfigParse {
+        path: String,
+        message: String,
+        line: Option<usize>,
+        column: Option<usize>,
+    },
+    /// A client config changed while MCPHub was about to write it.
+    ConfigConflict {
+        path: String,
+    },
+    /// A server needs Node.js or uv, and neither MCPHub nor the system provides it.
+    RuntimeMissing {
+        runtime: String,
+    },
+    /// Env values that don't fit the catalog's schema, one message per variable.
+    EnvInvalid {
+        server_id: String,
+        fields: Vec<EnvFieldError>,
+    },
+    Io {
+        message: String,
+    },
+    Network {
+        message: String,
+    },
+    /// Anything without a more specific variant.
+    Internal {
+        message: String,
+    },
+}
+
+/// A problem with one env variable, reported by [`AppError::EnvInvalid`].
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct EnvFieldError {
+    pub key: String,
+    pub message: String,
+}
+
+impl AppError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::CatalogMissing => "catalogMissing",
+            AppError::ServerNotFound { .. } => "serverNotFound",
+            AppError::ServerNotInstalled { .. } => "serverNotInstalled",
+            AppError::ClientNotFound { .. } => "clientNotFound",
+            AppError::ConfigParse { .. } => "configParse",
+            AppError::ConfigConflict { .. } => "configConflict",
+            AppError::RuntimeMissing { .. } => "runtimeMissing",
+            AppError::EnvInvalid { .. } => "envInvalid",
+            AppError::Io { .. } => "io",
+            AppError::Network { .. } => "network",
+            AppError::Internal { .. } => "internal",
+        }
+    }
+
+    fn context(&self) -> Value {
+        match self {
+            AppError::CatalogMissing => Value::Null,
+            AppError::ServerNotFound { server_id } | AppError::ServerNotInstalled { server_id } => {
+                json!({ "serverId": server_id })
+            }
+            AppError::ClientNotFound { client_id } => json!({ "clientId": client_id }),
+            AppError::ConfigParse {
+                path,
+                message,
+                line,
+                column,
+            } => json!({ "path": path, "message": message, "line": line, "column": column }),
+            AppError::ConfigConflict { path } => json!({ "path": path }),
+            AppError::RuntimeMissing { runtime } => json!({ "runtime": runtime }),
+            AppError::EnvInvalid { server_id, fields } => {
+                json!({ "serverId": server_id, "fields": fields })
+            }
+            AppError::Io { .. } | AppError::Network { .. } | AppError::Internal { .. } => {
+                Value::Null
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AppError::CatalogMissing => {
+                write!(f, "The server catalog has not been downloaded yet")
+            }
+            AppError::ServerNotFound { server_id } => {
+                write!(f, "Server not found in catalog: {}", server_id)
+            }
+            AppError::ServerNotInstalled { server_id } => {
+                write!(f, "{} is not configured", server_id)
+            }
+            AppError::ClientNotFound { client_id } => write!(f, "Unknown client: {}", client_id),
+            AppError::ConfigParse {
+                path,
+                message,
+                line: Some(line),
+                column: Some(column),
+            } => write!(
+                f,
+                "Failed to parse {} at line {}, column {}: {}",
+                path, line, column, message
+            ),
+            AppError::ConfigParse { path, message, .. } => {
+                write!(f, "Failed to parse {}: {}", path, message)
+            }
+            AppError::ConfigConflict { path } => write!(
+                f,
+                "{} was changed by another program; reload and try again",
+                path
+            ),
+            AppError::RuntimeMissing { runtime } => write!(
+                f,
+                "{} is not installed; install it from the welcome page first",
+                runtime
+            ),
+            AppError::EnvInvalid { server_id, fields } => {
+                let messages: Vec<&str> =
+                    fields.iter().map(|field| field.message.as_str()).collect();
+                write!(
+                    f,
+                    "Invalid settings for {}: {}",
+                    server_id,
+                    messages.join("; ")
+                )
+            }
+            AppError::Io { message }
+            | AppError::Network { message }
+            | AppError::Internal { message } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("context", &self.context())?;
+        state.end()
+    }
+}
+
+/// Core functions return `anyhow` errors; this picks the variant from the error
+/// itself or, failing that, from the first cause that tells what went wrong.
+impl From<anyhow::Error> for AppError {
+    fn from(error: anyhow::Error) -> Self {
+        let error = match error.downcast::<AppError>() {
+            Ok(app_error) => return app_error,
+            Err(error) => error,
+        };
+        if let Some(e) = error.downcast_ref::<ConfigParseError>() {
+            return AppError::ConfigParse {
+                path: e.path.to_string_lossy().to_string(),
+                message: e.message.clone(),
+                line: e.line,
+                column: e.column,
+            };
+        }
+        if let Some(e) = error.downcast_ref::<ConfigConflictError>() {
+            return AppError::ConfigConflict {
+                path: e.path.to_string_lossy().to_string(),
+            };
+        }
+        let message = format!("{:#}", error);
+        for cause in error.chain() {
+            if cause.is::<reqwest::Error>() {
+                return AppError::Network { message };
+            }
+            if cause.is::<std::io::Error>() {
+                return AppError::Io { message };
+            }
+        }
+        AppError::Internal { message }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+    use std::path::PathBuf;
+
+    fn serialized(error: anyhow::Error) -> Value {
+        serde_json::to_value(AppError::from(error)).unwrap()
+    }
+
+    #[test]
+    fn app_errors_pass_through_unchanged() {
+        let cases = [
+            (
+                AppError::CatalogMissing,
+                json!({
+                    "code": "catalogMissing",
+                    "message": "The server catalog has not been downloaded yet",
+                    "context": null,
+                }),
+            ),
+            (
+                AppError::ServerNotFound {
+                    server_id: "github".to_string(),
+                },
+                json!({
+                    "code": "serverNotFound",
+                    "message": "Server not found in catalog: github",
+                    "context": { "serverId": "github" },
+                }),
+            ),
+            (
+                AppError::ServerNotInstalled {
+                    server_id: "github".to_string(),
+                },
+                json!({
+                    "code": "serverNotInstalled",
+                    "message": "github is not configured",
+                    "context": { "serverId": "github" },
+                }),
+            ),
+            (
+                AppError::ClientNotFound {
+                    client_id: "emacs".to_string(),
+                },
+                json!({
+                    "code": "clientNotFound",
+                    "message": "Unknown client: emacs",
+                    "context": { "clientId": "emacs" },
+                }),
+            ),
+            (
+                AppError::RuntimeMissing {
+                    runtime: "uv".to_string(),
+                },
+                json!({
+                    "code": "runtimeMissing",
+                    "message": "uv is not installed; install it from the welcome page first",
+                    "context": { "runtime": "uv" },
+                }),
+            ),
+            (
+                AppError::EnvInvalid {
+                    server_id: "github".to_string(),
+                    fields: vec![
+                        EnvFieldError {
+                            key: "TOKEN".to_string(),
+                            message: "TOKEN is required".to_string(),
+                        },
+                        EnvFieldError {
+                            key: "PORT".to_string(),
+                            message: "PORT must be a number".to_string(),
+                        },
+                    ],
+                },
+                json!({
+                    "code": "envInvalid",
+                    "message": "Invalid settings for github: TOKEN is required; PORT must be a number",
+                    "context": {
+                        "serverId": "github",
+                        "fields": [
+                            { "key": "TOKEN", "message": "TOKEN is required" },
+                            { "key": "PORT", "message": "PORT must be a number" },
+                        ],
+                    },
+                }),
+            ),
+        ];
+        for (error, expected) in cases {
+            assert_eq!(serialized(anyhow::Error::new(error)), expected);
+        }
+    }
+
+    #[test]
+    fn config_parse_errors_keep_their_position() {
+        let error = ConfigParseError {
+            path: PathBuf::from("/home/me/.cursor/mcp.json"),
+            message: "expected `,` or `}`".to_string(),
+            line: Some(3),
+            column: Some(7),
+        };
+        assert_eq!(
+            serialized(anyhow::Error::new(error)),
+            json!({
+                "code": "configParse",
+                "message": "Failed to parse /home/me/.cursor/mcp.json at line 3, column 7: expected `,` or `}`",
+                "context": {
+                    "path": "/home/me/.cursor/mcp.json",
+                    "message": "expected `,` or `}`",
+                    "line": 3,
+                    "column": 7,
+                },
+            })
+        );
+
+        let error = ConfigParseError {
+            path: PathBuf::from("/home/me/.codex/config.toml"),
+            message: "invalid table header".to_string(),
+            line: None,
+            column: None,
+        };
+        assert_eq!(
+            serialized(anyhow::Error::new(error)),
+            json!({
+                "code": "configParse",
+                "message": "Failed to parse /home/me/.codex/config.toml: invalid table header",
+                "context": {
+                    "path": "/home/me/.codex/config.toml",
+                    "message": "invalid table header",
+                    "line": null,
+                    "column": null,
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn config_conflicts_name_the_file() {
+        let error = ConfigConflictError {
+            path: PathBuf::from("/home/me/.cursor/mcp.json"),
+        };
+        assert_eq!(
+            serialized(anyhow::Error::new(error)),
+            json!({
+                "code": "configConflict",
+                "message": "/home/me/.cursor/mcp.json was changed by another program; reload and try again",
+                "context": { "path": "/home/me/.cursor/mcp.json" },
+            })
+        );
+    }
+
+    #[test]
+    fn causes_pick_io_and_network_variants() {
+        let error = Err::<(), _>(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            "permission denied",
+        ))
+        .context("Failed to write /etc/mcp.json")
+        .unwrap_err();
+        assert_eq!(
+            serialized(error),
+            json!({
+                "code": "io",
+                "message": "Failed to write /etc/mcp.json: permission denied",
+                "context": null,
+            })
+        );
+
+        let request = reqwest::Client::new().get("not a url").build();
+        let error = anyhow::Error::new(request.unwrap_err()).context("Failed to fetch the catalog");
+        let value = serialized(error);
+        assert_eq!(value["code"], "network");
+        assert!(value["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("Failed to fetch the catalog: "));
+        assert_eq!(value["context"], Value::Null);
+    }
+
+    #[test]
+    fn other_errors_are_internal() {
+        let error = anyhow::anyhow!("no such profile").context("Failed to activate profile");
+        assert_eq!(
+            serialized(error),
+            json!({
+                "code": "internal",
+                "message": "Failed to activate profile: no such profile",
+                "context": null,
+            })
+        );
+    }
+}
@@ -0,0 +1,439 @@
+use crate::api::servers::core::{find_catalog_server, parse_catalog, RuntimeSettings, ServerArgv};
+use crate::clients::ClientServerConfig;
+use crate::utils::fs::{path_key, write_atomic};
+use crate::utils::secrets::get_secret;
+use crate::APP_STATE_FILENAME;
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tauri::Manager;
+
+/// First argument that starts MCPHub as a server launcher instead of the app.
+pub const LAUNCH_ARG: &str = "launch";
+
+/// Directory in the app data dir with a launcher per installed server.
+const LAUNCHERS_DIR: &str = "launchers";
+const SPEC_FILENAME: &str = "launch.json";
+#[cfg(not(windows))]
+const SCRIPT_FILENAME: &str = "run";
+#[cfg(windows)]
+const SCRIPT_FILENAME: &str = "run.cmd";
+
+/// What a server's launcher starts. Only the user's choices are fixed here: the
+/// command is worked out from the catalog and the runtime settings in MCPHub's state
+/// each time the server starts, so changes to those apply without reinstalling it.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LaunchSpec {
+    /// MCPHub's state file.
+    #[serde(rename = "appState")]
+    pub app_state: PathBuf,
+    #[serde(rename = "catalogId")]
+    pub catalog_id: String,
+    /// Values of the server's input arguments by name.
+    #[serde(rename = "inputArgs", default)]
+    pub input_args: BTreeMap<String, Vec<String>>,
+    /// Env variable names and the secret store accounts holding their values.
+    #[serde(default)]
+    pub secrets: BTreeMap<String, String>,
+}
+
+/// A server's launcher: a script the client config points at, which hands the spec
+/// next to it to MCPHub. The script's path never changes, so neither does the entry.
+#[derive(Debug, Clone)]
+pub struct Launcher {
+    dir: PathBuf,
+    pub spec: LaunchSpec,
+}
+
+impl Launcher {
+    /// The launcher of server `instance_id` in the client config at `config_path`.
+    pub fn new(
+        app_handle: &tauri::AppHandle,
+        config_path: &Path,
+        instance_id: &str,
+        spec: LaunchSpec,
+    ) -> Result<Self> {
+        let dir = app_handle
+            .path()
+            .app_data_dir()?
+            .join(LAUNCHERS_DIR)
+            .join(path_key(config_path))
+            .join(instance_id);
+        Ok(Launcher { dir, spec })
+    }
+
+    /// A launcher kept in `dir`, for tests that have no app data dir to put it in.
+    #[cfg(test)]
+    pub fn in_dir(dir: &Path, spec: LaunchSpec) -> Self {
+        Launcher {
+            dir: dir.to_path_buf(),
+            spec,
+        }
+    }
+
+    /// Reads the launcher saved in `dir`.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let spec_path = dir.join(SPEC_FILENAME);
+        let text = std::fs::read_to_string(&spec_path)
+            .with_context(|| format!("Failed to read {}", spec_path.to_string_lossy()))?;
+        Ok(Launcher {
+            dir: dir.to_path_buf(),
+            spec: serde_json::from_str(&text)?,
+        })
+    }
+
+    /// The launcher a config entry runs, or `None` when it runs its command directly.
+    pub fn from_entry(server_config: &ClientServerConfig) -> Option<Self> {
+        if !Self::runs_launcher(server_config) {
+            return None;
+        }
+        Launcher::load(entry_script(server_config)?.parent()?).ok()
+    }
+
+    /// Whether `server_config` runs a launcher script, which may have been removed since.
+    pub fn runs_launcher(server_config: &ClientServerConfig) -> bool {
+        entry_script(server_config)
+            .and_then(Path::file_name)
+            .is_some_and(|name| name == SCRIPT_FILENAME)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Points `server_config` at the launcher script, which takes no arguments.
+    pub fn write_to(&self, server_config: &mut ClientServerConfig) {
+        let script = self.dir.join(SCRIPT_FILENAME).to_string_lossy().to_string();
+        // Clients start commands without a shell, and only cmd runs batch files.
+        if cfg!(windows) {
+            server_config.command = "cmd".to_string();
+            server_config.args = vec!["/c".to_string(), script];
+        } else {
+            server_config.command = script;
+            server_config.args = vec![];
+        }
+    }
+
+    /// Writes the spec and the script.
+    pub fn save(&self) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        write_atomic(
+            &self.dir.join(SPEC_FILENAME),
+            serde_json::to_string_pretty(&self.spec)?.as_bytes(),
+        )?;
+        write_script(&self.dir)
+    }
+
+    pub fn remove(&self) -> Result<()> {
+        match std::fs::remove_dir_all(&self.dir) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The script an entry written by [`Launcher::write_to`] runs.
+fn entry_script(server_config: &ClientServerConfig) -> Option<&Path> {
+    match server_config.args.as_slice() {
+        [] => Some(Path::new(&server_config.command)),
+        [flag, script] if server_config.command == "cmd" && flag == "/c" => Some(Path::new(script)),
+        _ => None,
+    }
+}
+
+/// Writes the script that starts this executable as the launcher of the spec in `dir`.
+fn write_script(dir: &Path) -> Result<()> {
+    let exe = launcher_path()?.to_string_lossy().to_string();
+    let spec = dir.join(SPEC_FILENAME).to_string_lossy().to_string();
+    let script = dir.join(SCRIPT_FILENAME);
+    #[cfg(not(windows))]
+    {
+        use shell_escape::unix::escape;
+        use std::borrow::Cow;
+        use std::os::unix::fs::PermissionsExt;
+        let text = format!(
+            "#!/bin/sh\n# Written by MCPHub, which keeps it up to date.\nexec {} {} {}\n",
+            escape(Cow::from(exe)),
+            LAUNCH_ARG,
+            escape(Cow::from(spec))
+        );
+        write_atomic(&script, text.as_bytes())?;
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755))?;
+    }
+    #[cfg(windows)]
+    {
+        // Paths can't hold quotes, but a `%` in one would be expanded.
+        let quote = |path: String| format!("\"{}\"", path.replace('%', "%%"));
+        let text = format!(
+            "@echo off\r\nrem Written by MCPHub, which keeps it up to date.\r\n{} {} {}\r\n",
+            quote(exe),
+            LAUNCH_ARG,
+            quote(spec)
+        );
+        write_atomic(&script, text.as_bytes())?;
+    }
+    Ok(())
+}
+
+/// Rewrites every launcher script, so that they start the MCPHub executable running
+/// now even if it moved since they were written.
+pub fn refresh_launchers(app_handle: &tauri::AppHandle) -> Result<()> {
+    let launchers_dir = app_handle.path().app_data_dir()?.join(LAUNCHERS_DIR);
+    let pattern = launchers_dir.join("*").join("*").join(SPEC_FILENAME);
+    for spec_path in glob::glob(&pattern.to_string_lossy())?.flatten() {
+        if let Some(dir) = spec_path.parent() {
+            write_script(dir)?;
+        }
+    }
+    Ok(())
+}
+
+/// Where launcher scripts find MCPHub, i.e. this executable.
+fn launcher_path() -> Result<PathBuf> {
+    // An AppImage runs from a mount point that is gone once MCPHub quits.
+    if let Some(appimage) = std::env::var_os("APPIMAGE") {
+        return Ok(PathBuf::from(appimage));
+    }
+    std::env::current_exe().context("Failed to locate the MCPHub executable")
+}
+
+/// Path of MCPHub's state file, which launchers read the catalog and runtimes from.
+pub fn app_state_path(app_handle: &tauri::AppHandle) -> Result<PathBuf> {
+    Ok(app_handle.path().app_data_dir()?.join(APP_STATE_FILENAME))
+}
+
+/// Runs the launcher with the arguments that follow [`LAUNCH_ARG`] and exits with the
+/// server's status. Errors go to stderr, which clients show in their server logs.
+pub fn run_launcher(args: Vec<String>) -> ! {
+    let result = match args.as_slice() {
+        [spec_path] => launch(Path::new(spec_path)),
+        _ => Err(anyhow!("Usage: {} <launch spec>", LAUNCH_ARG)),
+    };
+    match result {
+        Ok(code) => std::process::exit(code),
+        Err(e) => {
+            eprintln!("MCPHub launcher: {:#}", e);
+            std::process::exit(1)
+        }
+    }
+}
+
+fn launch(spec_path: &Path) -> Result<i32> {
+    let spec = Launcher::load(spec_path.parent().unwrap_or(Path::new(".")))?.spec;
+    let text = std::fs::read_to_string(&spec.app_state)
+        .with_context(|| format!("Failed to read {}", spec.app_state.to_string_lossy()))?;
+    let mut state: Map<String, Value> = serde_json::from_str(&text)?;
+    let settings = RuntimeSettings::from_lookup(|key| state.get(key).cloned());
+    let server = find_catalog_server(parse_catalog(state.remove("servers"))?, &spec.catalog_id)?;
+    let input_args = server.input_args(Some(spec.input_args))?;
+    let argv = server.argv(&input_args, Some(&settings))?;
+    let program = argv.program.clone();
+    let mut command = argv_command(&argv)?;
+
+    for (key, account) in &spec.secrets {
+        let value = get_secret(account)?.ok_or_else(|| {
+            anyhow!(
+                "Secret {} is missing from the secret store; set it again in MCPHub",
+                key
+            )
+        })?;
+        command.env(key, value);
+    }
+
+    // The server takes over this process, so the client talks to it directly.
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        let e = command.exec();
+        Err(e).with_context(|| format!("Failed to start {}", program))
+    }
+    #[cfg(not(unix))]
+    {
+        // MCPHub is a GUI program, so a console server would get a window of its own.
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+            command.creation_flags(CREATE_NO_WINDOW);
+        }
+        let status = command
+            .status()
+            .with_context(|| format!("Failed to start {}", program))?;
+        Ok(status.code().unwrap_or(1))
+    }
+}
+
+/// The process that runs `argv`, with its runtime directory put in front of PATH.
+fn argv_command(argv: &ServerArgv) -> Result<Command> {
+    let mut path = std::env::var_os("PATH").unwrap_or_default();
+    if let Some(runtime_dir) = &argv.runtime_dir {
+        let dirs = std::iter::once(runtime_dir.clone()).chain(std::env::split_paths(&path));
+        path = std::env::join_paths(dirs)?;
+    }
+    // std only looks for .exe files, while npx and uvx may be batch files. Given the
+    // full path of one, it runs it through cmd with every argument escaped for cmd.
+    #[cfg(windows)]
+    let program =
+        find_program(&argv.program, &path).unwrap_or_else(|| PathBuf::from(&argv.program));
+    #[cfg(not(windows))]
+    let program = PathBuf::from(&argv.program);
+    let mut command = Command::new(program);
+    command.args(&argv.args).env("PATH", path);
+    Ok(command)
+}
+
+/// Looks `program` up in `path` the way cmd does, trying the extensions in PATHEXT.
+#[cfg(windows)]
+fn find_program(program: &str, path: &std::ffi::OsStr) -> Option<PathBuf> {
+    let name = Path::new(program);
+    if name.components().count() != 1 {
+        return None;
+    }
+    let extensions: Vec<String> = if name.extension().is_some() {
+        vec![String::new()]
+    } else {
+        std::env::var("PATHEXT")
+            .unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string())
+            .split(';')
+            .filter(|extension| !extension.is_empty())
+            .map(String::from)
+            .collect()
+    };
+    std::env::split_paths(path).find_map(|dir| {
+        extensions
+            .iter()
+            .map(|extension| dir.join(format!("{}{}", program, extension)))
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::servers::core::BackendServer;
+    use serde_json::json;
+
+    fn backend_server(command: &str, args: &[&str], input_args: Value) -> BackendServer {
+        serde_json::from_value(json!({
+            "id": "demo",
+            "title": "Demo",
+            "description": "",
+            "creator": "",
+            "tags": [],
+            "logoUrl": "",
+            "rating": 5,
+            "publishDate": "",
+            "commandInfo": { "command": command, "args": args, "inputArgs": input_args },
+        }))
+        .unwrap()
+    }
+
+    fn values(pairs: &[(&str, &[&str])]) -> BTreeMap<String, Vec<String>> {
+        pairs
+            .iter()
+            .map(|(name, values)| {
+                let values = values.iter().map(|value| value.to_string()).collect();
+                (name.to_string(), values)
+            })
+            .collect()
+    }
+
+    /// The arguments the launcher would start the server with.
+    fn command_args(
+        server: &BackendServer,
+        given: BTreeMap<String, Vec<String>>,
+        runtime: Option<&RuntimeSettings>,
+    ) -> (Command, Vec<String>) {
+        let input_args = server.input_args(Some(given)).unwrap();
+        let command = argv_command(&server.argv(&input_args, runtime).unwrap()).unwrap();
+        let args = command
+            .get_args()
+            .map(|arg| arg.to_str().unwrap().to_string())
+            .collect();
+        (command, args)
+    }
+
+    #[test]
+    fn input_values_reach_the_server_unchanged() {
+        let server = backend_server(
+            "npx",
+            &["-y", "@modelcontextprotocol/server-filesystem"],
+            json!([{ "name": "paths", "class": "DirectoryPath", "multiplicity": "Multiple" }]),
+        );
+        let paths = [
+            "/home/me/My Documents",
+            "it's",
+            "say \"hi\"",
+            "$HOME `whoami` ; & | > *",
+            "%PATH% ^ !x!",
+            "Jö 日本 🎉",
+            r"C:\Users\John Doe\My Documents\",
+            r"\\server\share\dir",
+            "--flag=x y",
+        ];
+        let (command, args) = command_args(&server, values(&[("paths", &paths)]), None);
+        assert_eq!(command.get_program(), "npx");
+        let mut expected = vec!["-y", "@modelcontextprotocol/server-filesystem"];
+        expected.extend(paths);
+        assert_eq!(args, expected);
+    }
+
+    #[test]
+    fn input_arguments_follow_catalog_order() {
+        let server = backend_server(
+            "uvx",
+            &["db-server"],
+            json!([
+                { "name": "url", "flag": "--db" },
+                { "name": "readonly", "flag": "--readonly", "class": "Switch" },
+                { "name": "dirs", "multiplicity": "Multiple" },
+                { "name": "level", "flag": "--level=" },
+            ]),
+        );
+        let given = values(&[
+            ("level", &["debug info"]),
+            ("dirs", &["/a", "", "/b c"]),
+            ("readonly", &["true"]),
+            ("url", &["postgres://u:p@host/db name"]),
+        ]);
+        let (_, args) = command_args(&server, given, None);
+        assert_eq!(
+            args,
+            [
+                "db-server",
+                "--db",
+                "postgres://u:p@host/db name",
+                "--readonly",
+                "/a",
+                "/b c",
+                "--level=debug info",
+            ]
+        );
+
+        let given = values(&[("url", &[""]), ("readonly", &["false"]), ("dirs", &[""])]);
+        let (_, args) = command_args(&server, given, None);
+        assert_eq!(args, ["db-server"]);
+    }
+
+    #[test]
+    fn runtime_dir_goes_in_front_of_path() {
+        let server = backend_server("npx", &["-y", "pkg"], json!([]));
+        let node_dir = "/opt/node's \"bin\" $x ö";
+        let settings = RuntimeSettings::from_lookup(|key| match key {
+            "node_path" => Some(json!(node_dir)),
+            _ => None,
+        });
+        let (command, args) = command_args(&server, BTreeMap::new(), Some(&settings));
+        assert_eq!(args, ["-y", "pkg"]);
+        let path = command
+            .get_envs()
+            .find(|(key, _)| *key == "PATH")
+            .and_then(|(_, value)| value)
+            .unwrap();
+        let first = std::env::split_paths(path).next().unwrap();
+        assert_eq!(first, PathBuf::from(node_dir));
+    }
+}
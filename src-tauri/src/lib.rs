@@ -1,4 +1,7 @@
 mod api;
+mod clients;
+mod error;
+mod launcher;
 mod utils;
 
 use tauri_plugin_log::{Target, TargetKind};
@@ -6,8 +9,18 @@ use tauri_plugin_store::StoreExt;
 
 pub const APP_STATE_FILENAME: &str = "AppState.json";
 
+pub use launcher::{run_launcher, LAUNCH_ARG};
+
+use api::backups::view as backups_view;
+use api::bundles::view as bundles_view;
+use api::clients::view as clients_view;
 use api::dependency::view as dependency_view;
+use api::history::view as history_view;
+use api::profiles::view as profiles_view;
+use api::secrets::view as secrets_view;
 use api::servers::view as servers_view;
+use api::watcher::core as watcher_core;
+use api::watcher::view as watcher_view;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -26,16 +39,52 @@ pub fn run() {
                 .build(),
         )
         .plugin(tauri_plugin_store::Builder::new().build())
+        .manage(clients::ConfigMutations::default())
+        .setup(|app| {
+            if let Err(e) = watcher_core::start_watching(app.handle()) {
+                log::warn!("Failed to start watching client configs: {}", e);
+            }
+            if let Err(e) = launcher::refresh_launchers(app.handle()) {
+                log::warn!("Failed to refresh server launchers: {}", e);
+            }
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
+            backups_view::list_client_config_backups,
+            backups_view::restore_client_config_backup,
+            bundles_view::export_server_bundle,
+            bundles_view::inspect_server_bundle,
+            bundles_view::import_server_bundle,
+            clients_view::get_clients,
+            clients_view::restart_client,
             dependency_view::check_dependency,
             dependency_view::check_resource,
             dependency_view::install_npm,
             dependency_view::install_uv,
+            history_view::get_config_history,
+            history_view::diff_config_versions,
+            history_view::revert_config_change,
+            profiles_view::get_profiles,
+            profiles_view::create_profile,
+            profiles_view::clone_profile,
+            profiles_view::delete_profile,
+            profiles_view::activate_profile,
+            secrets_view::rotate_server_secret,
+            secrets_view::reveal_server_secret,
+            secrets_view::delete_server_secret,
             servers_view::get_servers,
             servers_view::get_installed_servers,
             servers_view::install_server,
             servers_view::uninstall_server,
             servers_view::update_server,
+            servers_view::get_unmanaged_servers,
+            servers_view::adopt_server,
+            servers_view::disable_server,
+            servers_view::enable_server,
+            servers_view::preview_install_server,
+            servers_view::preview_update_server,
+            servers_view::preview_uninstall_server,
+            watcher_view::watch_client_config,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
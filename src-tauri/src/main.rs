@@ -2,5 +2,9 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() == Some(mcphub_desktop_lib::LAUNCH_ARG) {
+        mcphub_desktop_lib::run_launcher(args.collect());
+    }
     mcphub_desktop_lib::run()
 }
@@ -0,0 +1,140 @@
+use anyhow::{anyhow, bail, Result};
+use fs2::FileExt;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// How long `lock_file` waits for another process to release its lock.
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Replaces `path` with `contents` so readers only ever see the old or the new file.
+///
+/// The data goes to a temporary file next to `path`, is flushed to disk, and is then
+/// renamed over the original. Permissions of an existing file are kept.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let parent = path
+        .parent()
+        .ok_or_else(|| anyhow!("{} has no parent directory", path.to_string_lossy()))?;
+    std::fs::create_dir_all(parent)?;
+
+    let temp_path = temp_path_for(path)?;
+    let result = write_temp(&temp_path, path, contents)
+        .and_then(|_| std::fs::rename(&temp_path, path).map_err(Into::into));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    result?;
+
+    // Persist the rename itself; Windows has no directory handles to sync.
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    File::open(parent)?.sync_all()?;
+
+    Ok(())
+}
+
+fn write_temp(temp_path: &Path, path: &Path, contents: &[u8]) -> Result<()> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(temp_path)?;
+    if let Ok(metadata) = std::fs::metadata(path) {
+        file.set_permissions(metadata.permissions())?;
+    }
+    file.write_all(contents)?;
+    file.sync_all()?;
+    Ok(())
+}
+
+fn temp_path_for(path: &Path) -> Result<PathBuf> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow!("{} is not a file path", path.to_string_lossy()))?;
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(file_name);
+    temp_name.push(format!(".{}.tmp", std::process::id()));
+    Ok(path.with_file_name(temp_name))
+}
+
+/// Takes an exclusive advisory lock on `lock_path`, creating the file if needed. The
+/// lock is held until the returned file is dropped.
+pub fn lock_file(lock_path: &Path) -> Result<File> {
+    lock_file_within(lock_path, LOCK_TIMEOUT)
+}
+
+fn lock_file_within(lock_path: &Path, timeout: Duration) -> Result<File> {
+    if let Some(parent) = lock_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(lock_path)?;
+    let started = Instant::now();
+    while let Err(e) = file.try_lock_exclusive() {
+        if e.kind() != fs2::lock_contended_error().kind() {
+            return Err(e.into());
+        }
+        if started.elapsed() > timeout {
+            bail!(
+                "{} is locked by another process",
+                lock_path.to_string_lossy()
+            );
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    Ok(file)
+}
+
+/// Name for a file of MCPHub's own that belongs to `path`, built from its full path so
+/// files for different configs never collide.
+pub fn path_key(path: &Path) -> String {
+    let key: String = path
+        .to_string_lossy()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    key.trim_start_matches('_').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locks_are_exclusive_until_dropped() {
+        let dir = tempfile::tempdir().unwrap();
+        let lock_path = dir.path().join("locks").join("config.lock");
+        let held = lock_file(&lock_path).unwrap();
+        assert!(lock_path.exists());
+
+        let started = Instant::now();
+        let error = lock_file_within(&lock_path, Duration::from_millis(200)).unwrap_err();
+        assert!(error.to_string().contains("is locked by another process"));
+        assert!(started.elapsed() >= Duration::from_millis(200));
+
+        drop(held);
+        lock_file_within(&lock_path, Duration::ZERO).unwrap();
+    }
+
+    #[test]
+    fn locks_wait_for_the_holder_to_let_go() {
+        let dir = tempfile::tempdir().unwrap();
+        let lock_path = dir.path().join("config.lock");
+        let held = lock_file(&lock_path).unwrap();
+        let holder = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(200));
+            drop(held);
+        });
+        lock_file_within(&lock_path, Duration::from_secs(5)).unwrap();
+        holder.join().unwrap();
+    }
+}
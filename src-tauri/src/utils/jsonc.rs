@@ -0,0 +1,615 @@
+//! Reading and editing JSON-with-comments files (VS Code and Zed settings) so that
+//! MCPHub only rewrites the values it owns and leaves comments, key order and
+//! formatting of everything else untouched.
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::Value;
+
+const DEFAULT_INDENT: &str = "    ";
+
+/// Returns `text` with comments and trailing commas blanked out by spaces, so byte
+/// offsets and line numbers still line up with the original.
+pub fn strip(text: &str) -> String {
+    let mut bytes = strip_comments(text).into_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => i = skip_string(&bytes, i),
+            b',' => {
+                if let Some(next) = next_significant(&bytes, i + 1) {
+                    if bytes[next] == b'}' || bytes[next] == b']' {
+                        bytes[i] = b' ';
+                    }
+                }
+                i += 1;
+            }
+            _ => i += 1,
+        }
+    }
+
+    // Only ASCII bytes outside of strings were replaced, so this cannot fail.
+    String::from_utf8(bytes).unwrap_or_default()
+}
+
+/// Like [`strip`] but keeps trailing commas, which edits need to see.
+fn strip_comments(text: &str) -> String {
+    let mut bytes = text.as_bytes().to_vec();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => i = skip_string(&bytes, i),
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' && bytes[i] != b'\r' {
+                    bytes[i] = b' ';
+                    i += 1;
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                let end = find_block_comment_end(&bytes, i + 2);
+                for byte in &mut bytes[i..end] {
+                    if *byte != b'\n' && *byte != b'\r' {
+                        *byte = b' ';
+                    }
+                }
+                i = end;
+            }
+            _ => i += 1,
+        }
+    }
+
+    // Comment bytes are all replaced, so any multi-byte characters in them are gone
+    // as a whole and this cannot fail.
+    String::from_utf8(bytes).unwrap_or_default()
+}
+
+/// Parses a JSONC document. An empty or whitespace-only document is an empty object.
+pub fn parse(text: &str) -> Result<Value> {
+    let stripped = strip(text);
+    if stripped.trim().is_empty() {
+        return Ok(Value::Object(Default::default()));
+    }
+    Ok(serde_json::from_str(&stripped)?)
+}
+
+/// Follows `path` through the nested objects of a parsed document.
+pub fn get<'a>(value: &'a Value, path: &[&str]) -> Option<&'a Value> {
+    path.iter().try_fold(value, |value, key| value.get(key))
+}
+
+/// Sets the value at `path`, creating intermediate objects as needed.
+pub fn set_value(text: &str, path: &[&str], value: &Value) -> Result<String> {
+    if path.is_empty() {
+        bail!("Cannot replace the document root");
+    }
+    parse(text)?;
+    let layout = Layout::detect(text);
+    let stripped = strip_comments(text);
+    if stripped.trim().is_empty() {
+        let document = to_pretty(&nest(path, value), &layout, "");
+        return Ok(format!("{}{}", document, layout.newline));
+    }
+
+    let root = Parser::new(stripped.as_bytes()).parse_document()?;
+    let mut node = &root;
+    for (depth, key) in path.iter().enumerate() {
+        let NodeKind::Object(properties) = &node.kind else {
+            bail!("`{}` is not an object", path[..depth].join("."));
+        };
+        match properties.iter().find(|property| property.key == *key) {
+            Some(property) if depth == path.len() - 1 => {
+                let indent = line_indent(text, property.start);
+                let replacement = to_pretty(value, &layout, indent);
+                return Ok(splice(
+                    text,
+                    property.value.start,
+                    property.value.end,
+                    &replacement,
+                ));
+            }
+            Some(property) => node = &property.value,
+            None => {
+                let nested = nest(&path[depth + 1..], value);
+                return Ok(insert_property(
+                    text, &stripped, node, properties, key, &nested, &layout,
+                ));
+            }
+        }
+    }
+    unreachable!("the loop returns on the last path segment")
+}
+
+/// Removes the property at `path`. Missing properties are not an error.
+pub fn remove_value(text: &str, path: &[&str]) -> Result<String> {
+    let Some((last, parents)) = path.split_last() else {
+        bail!("Cannot remove the document root");
+    };
+    parse(text)?;
+    let stripped = strip_comments(text);
+    if stripped.trim().is_empty() {
+        return Ok(text.to_string());
+    }
+
+    let root = Parser::new(stripped.as_bytes()).parse_document()?;
+    let mut node = &root;
+    for key in parents {
+        let NodeKind::Object(properties) = &node.kind else {
+            return Ok(text.to_string());
+        };
+        match properties.iter().find(|property| property.key == *key) {
+            Some(property) => node = &property.value,
+            None => return Ok(text.to_string()),
+        }
+    }
+    let NodeKind::Object(properties) = &node.kind else {
+        return Ok(text.to_string());
+    };
+    let Some(index) = properties.iter().position(|property| property.key == *last) else {
+        return Ok(text.to_string());
+    };
+
+    let bytes = stripped.as_bytes();
+    let property = &properties[index];
+    let mut start = property.start;
+    let mut end = property.value.end;
+    let mut separator = None;
+    match next_significant(bytes, end) {
+        Some(comma) if bytes[comma] == b',' => end = comma + 1,
+        _ if index > 0 => {
+            // Last property without a trailing comma: drop the comma that separated it instead.
+            separator = next_significant(bytes, properties[index - 1].value.end)
+                .filter(|&comma| bytes[comma] == b',');
+        }
+        _ => {}
+    }
+
+    // Take the whole line when the property starts on a line of its own, along with
+    // a comment trailing it there.
+    let line_start = text[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    if line_start > node.start && text[line_start..start].trim().is_empty() {
+        start = line_start - 1;
+        if start > 0 && bytes[start - 1] == b'\r' {
+            start -= 1;
+        }
+        let line_end = text[end..]
+            .find(['\r', '\n'])
+            .map(|i| end + i)
+            .unwrap_or(text.len());
+        if text[end..line_end].trim_start().starts_with("//") {
+            end = line_end;
+        }
+    }
+
+    let mut result = String::with_capacity(text.len());
+    match separator {
+        Some(comma) if comma < start => {
+            result.push_str(&text[..comma]);
+            result.push_str(&text[comma + 1..start]);
+        }
+        _ => result.push_str(&text[..start]),
+    }
+    result.push_str(&text[end..]);
+    Ok(result)
+}
+
+fn insert_property(
+    text: &str,
+    stripped: &str,
+    object: &Node,
+    properties: &[Property],
+    key: &str,
+    value: &Value,
+    layout: &Layout,
+) -> String {
+    let newline = layout.newline;
+    let key = Value::String(key.to_string()).to_string();
+    match properties.last() {
+        Some(last) if same_line(text, object.start, last.start) => {
+            let snippet = format!(", {}: {}", key, value);
+            splice(text, last.value.end, last.value.end, &snippet)
+        }
+        Some(last) => {
+            let indent = line_indent(text, last.start);
+            let value = to_pretty(value, layout, indent);
+            let mut entry = format!("{}{}{}: {}", newline, indent, key, value);
+            let bytes = stripped.as_bytes();
+            let comma = next_significant(bytes, last.value.end).filter(|&i| bytes[i] == b',');
+            let anchor = comma.map(|i| i + 1).unwrap_or(last.value.end);
+            // Go past a comment trailing the last entry so it stays on its line.
+            let line_end = stripped[anchor..]
+                .find('\n')
+                .map(|i| anchor + i)
+                .unwrap_or(stripped.len());
+            let mut insert_at = if stripped[anchor..line_end].trim().is_empty() {
+                line_end
+            } else {
+                anchor
+            };
+            if insert_at > anchor && bytes[insert_at - 1] == b'\r' {
+                insert_at -= 1;
+            }
+            match comma {
+                // Keep the file's trailing comma style.
+                Some(_) => {
+                    entry.push(',');
+                    splice(text, insert_at, insert_at, &entry)
+                }
+                None => format!(
+                    "{},{}{}{}",
+                    &text[..last.value.end],
+                    &text[last.value.end..insert_at],
+                    entry,
+                    &text[insert_at..]
+                ),
+            }
+        }
+        None => {
+            let parent_indent = line_indent(text, object.start);
+            let indent = format!("{}{}", parent_indent, layout.unit);
+            let entry = format!("{}: {}", key, to_pretty(value, layout, &indent));
+            if text[object.start + 1..object.end - 1].trim().is_empty() {
+                let snippet = format!("{}{}{}{}{}", newline, indent, entry, newline, parent_indent);
+                splice(text, object.start + 1, object.end - 1, &snippet)
+            } else {
+                // The object only holds comments; keep them below the new entry.
+                let snippet = format!("{}{}{},", newline, indent, entry);
+                splice(text, object.start + 1, object.start + 1, &snippet)
+            }
+        }
+    }
+}
+
+fn nest(path: &[&str], value: &Value) -> Value {
+    path.iter().rev().fold(value.clone(), |inner, key| {
+        let mut object = serde_json::Map::new();
+        object.insert(key.to_string(), inner);
+        Value::Object(object)
+    })
+}
+
+fn splice(text: &str, start: usize, end: usize, replacement: &str) -> String {
+    format!("{}{}{}", &text[..start], replacement, &text[end..])
+}
+
+fn same_line(text: &str, a: usize, b: usize) -> bool {
+    !text[a.min(b)..a.max(b)].contains('\n')
+}
+
+fn line_indent(text: &str, offset: usize) -> &str {
+    let line_start = text[..offset].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let line = &text[line_start..];
+    let width = line.len() - line.trim_start_matches([' ', '\t']).len();
+    &line[..width]
+}
+
+/// How a document lays out what MCPHub adds to it.
+struct Layout {
+    /// One level of indentation.
+    unit: String,
+    newline: &'static str,
+}
+
+impl Layout {
+    /// Guesses the indentation unit from the first indented line, and the line ending
+    /// from the first line break.
+    fn detect(text: &str) -> Self {
+        let unit = text
+            .lines()
+            .map(|line| &line[..line.len() - line.trim_start_matches([' ', '\t']).len()])
+            .find(|indent| !indent.is_empty())
+            .map(|indent| {
+                if indent.starts_with('\t') {
+                    "\t".to_string()
+                } else {
+                    indent.to_string()
+                }
+            })
+            .unwrap_or_else(|| DEFAULT_INDENT.to_string());
+        let newline = match text.find('\n') {
+            Some(i) if text[..i].ends_with('\r') => "\r\n",
+            _ => "\n",
+        };
+        Layout { unit, newline }
+    }
+}
+
+fn to_pretty(value: &Value, layout: &Layout, indent: &str) -> String {
+    use serde::Serialize;
+
+    let mut buffer = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(layout.unit.as_bytes());
+    let mut serializer = serde_json::Serializer::with_formatter(&mut buffer, formatter);
+    // Serializing a `Value` into memory cannot fail.
+    value.serialize(&mut serializer).unwrap_or_default();
+    String::from_utf8(buffer)
+        .unwrap_or_default()
+        .replace('\n', &format!("{}{}", layout.newline, indent))
+}
+
+fn skip_string(bytes: &[u8], start: usize) -> usize {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+fn find_block_comment_end(bytes: &[u8], from: usize) -> usize {
+    let mut i = from;
+    while i + 1 < bytes.len() {
+        if bytes[i] == b'*' && bytes[i + 1] == b'/' {
+            return i + 2;
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+/// Offset of the next non-whitespace byte. Expects comments to be stripped already.
+fn next_significant(bytes: &[u8], from: usize) -> Option<usize> {
+    (from..bytes.len()).find(|&i| !bytes[i].is_ascii_whitespace())
+}
+
+struct Node {
+    start: usize,
+    end: usize,
+    kind: NodeKind,
+}
+
+enum NodeKind {
+    Object(Vec<Property>),
+    Array,
+    Scalar,
+}
+
+struct Property {
+    key: String,
+    /// Offset of the opening quote of the key.
+    start: usize,
+    value: Node,
+}
+
+/// Locates values in a stripped document. The document has already been validated by
+/// `serde_json`, so this only needs to track where things are.
+struct Parser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Parser { bytes, pos: 0 }
+    }
+
+    fn parse_document(&mut self) -> Result<Node> {
+        let node = self.parse_value()?;
+        if !matches!(node.kind, NodeKind::Object(_)) {
+            bail!("Expected the document to be a JSON object");
+        }
+        Ok(node)
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.pos = next_significant(self.bytes, self.pos).unwrap_or(self.bytes.len());
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<()> {
+        self.skip_whitespace();
+        if self.bytes.get(self.pos) != Some(&byte) {
+            bail!("Expected `{}` at offset {}", byte as char, self.pos);
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn parse_value(&mut self) -> Result<Node> {
+        self.skip_whitespace();
+        let start = self.pos;
+        match self.bytes.get(start) {
+            Some(b'{') => self.parse_object(),
+            Some(b'[') => self.parse_array(),
+            Some(b'"') => {
+                self.pos = skip_string(self.bytes, start);
+                Ok(Node {
+                    start,
+                    end: self.pos,
+                    kind: NodeKind::Scalar,
+                })
+            }
+            Some(_) => {
+                while self.pos < self.bytes.len()
+                    && !matches!(self.bytes[self.pos], b',' | b'}' | b']')
+                    && !self.bytes[self.pos].is_ascii_whitespace()
+                {
+                    self.pos += 1;
+                }
+                Ok(Node {
+                    start,
+                    end: self.pos,
+                    kind: NodeKind::Scalar,
+                })
+            }
+            None => Err(anyhow!("Unexpected end of document")),
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Node> {
+        let start = self.pos;
+        self.pos += 1;
+        let mut properties = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.bytes.get(self.pos) {
+                Some(b'}') => break,
+                Some(b',') => self.pos += 1,
+                Some(b'"') => {
+                    let key_start = self.pos;
+                    self.pos = skip_string(self.bytes, key_start);
+                    let raw_key = std::str::from_utf8(&self.bytes[key_start..self.pos])?;
+                    let key: String = serde_json::from_str(raw_key)
+                        .with_context(|| format!("Invalid key at offset {}", key_start))?;
+                    self.expect(b':')?;
+                    let value = self.parse_value()?;
+                    properties.push(Property {
+                        key,
+                        start: key_start,
+                        value,
+                    });
+                }
+                _ => bail!("Unexpected character at offset {}", self.pos),
+            }
+        }
+        self.pos += 1;
+        Ok(Node {
+            start,
+            end: self.pos,
+            kind: NodeKind::Object(properties),
+        })
+    }
+
+    fn parse_array(&mut self) -> Result<Node> {
+        let start = self.pos;
+        self.pos += 1;
+        loop {
+            self.skip_whitespace();
+            match self.bytes.get(self.pos) {
+                Some(b']') => break,
+                Some(b',') => self.pos += 1,
+                Some(_) => {
+                    self.parse_value()?;
+                }
+                None => bail!("Unterminated array starting at offset {}", start),
+            }
+        }
+        self.pos += 1;
+        Ok(Node {
+            start,
+            end: self.pos,
+            kind: NodeKind::Array,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const SETTINGS: &str = r#"{
+    // Editor settings
+    "editor.fontSize": 14,
+    "mcp": {
+        "servers": {
+            "first": { "command": "a" }, // the first one
+            /* block */ "middle": { "command": "b" },
+            "last": { "command": "c" }, // the last one
+        },
+    },
+}
+"#;
+
+    #[test]
+    fn comments_and_trailing_commas_parse() {
+        let document = parse(SETTINGS).unwrap();
+        assert_eq!(
+            get(&document, &["mcp", "servers", "last", "command"]),
+            Some(&json!("c"))
+        );
+        assert_eq!(get(&document, &["editor.fontSize"]), Some(&json!(14)));
+        assert_eq!(parse(" \n").unwrap(), json!({}));
+    }
+
+    #[test]
+    fn setting_a_value_keeps_comments_and_trailing_commas() {
+        let text = set_value(
+            SETTINGS,
+            &["mcp", "servers", "new"],
+            &json!({ "command": "d" }),
+        )
+        .unwrap();
+        assert!(text.contains(
+            "\"last\": { \"command\": \"c\" }, // the last one\n            \"new\": {\n                \"command\": \"d\"\n            },\n        },"
+        ));
+        assert!(text.contains("// Editor settings"));
+        assert!(text.contains("// the first one"));
+
+        let text = set_value(
+            SETTINGS,
+            &["mcp", "servers", "middle", "command"],
+            &json!("e"),
+        )
+        .unwrap();
+        assert!(text.contains("/* block */ \"middle\": { \"command\": \"e\" },"));
+    }
+
+    #[test]
+    fn inserts_go_first_into_empty_objects_and_last_into_others() {
+        let text = set_value("{}", &["servers", "only"], &json!(1)).unwrap();
+        assert_eq!(text, "{\n    \"servers\": {\n        \"only\": 1\n    }\n}");
+        let text = set_value(&text, &["servers", "next"], &json!(2)).unwrap();
+        assert_eq!(
+            text,
+            "{\n    \"servers\": {\n        \"only\": 1,\n        \"next\": 2\n    }\n}"
+        );
+        let text = set_value("", &["servers", "only"], &json!(1)).unwrap();
+        assert_eq!(
+            text,
+            "{\n    \"servers\": {\n        \"only\": 1\n    }\n}\n"
+        );
+    }
+
+    #[test]
+    fn removing_the_first_and_last_entries_takes_their_comments() {
+        let text = remove_value(SETTINGS, &["mcp", "servers", "first"]).unwrap();
+        assert!(!text.contains("first"));
+        assert!(text.contains("\"servers\": {\n            /* block */ \"middle\""));
+        parse(&text).unwrap();
+
+        let text = remove_value(SETTINGS, &["mcp", "servers", "last"]).unwrap();
+        assert!(!text.contains("last"));
+        assert!(text.contains("\"middle\": { \"command\": \"b\" },\n        },"));
+        parse(&text).unwrap();
+
+        let text = "{\n    \"a\": 1, // about a\n    \"b\": 2 // about b\n}";
+        assert_eq!(
+            remove_value(text, &["b"]).unwrap(),
+            "{\n    \"a\": 1 // about a\n}"
+        );
+        assert_eq!(
+            remove_value(text, &["a"]).unwrap(),
+            "{\n    \"b\": 2 // about b\n}"
+        );
+        let text = remove_value("{ \"a\": 1 }", &["a"]).unwrap();
+        assert_eq!(parse(&text).unwrap(), json!({}));
+        assert_eq!(remove_value(text.as_str(), &["a"]).unwrap(), text);
+    }
+
+    #[test]
+    fn crlf_documents_keep_their_line_endings() {
+        let text = SETTINGS.replace('\n', "\r\n");
+        let inserted = set_value(
+            &text,
+            &["mcp", "servers", "new"],
+            &json!({ "command": "d" }),
+        )
+        .unwrap();
+        let removed = remove_value(&inserted, &["mcp", "servers", "last"]).unwrap();
+        for text in [&inserted, &removed] {
+            assert!(!text.replace("\r\n", "").contains('\n'));
+            assert!(!text.replace("\r\n", "").contains('\r'));
+        }
+        assert_eq!(
+            get(&parse(&removed).unwrap(), &["mcp", "servers", "new"]),
+            Some(&json!({ "command": "d" }))
+        );
+
+        let text = set_value("{\r\n}", &["servers", "only"], &json!({ "a": 1 })).unwrap();
+        assert_eq!(
+            text,
+            "{\r\n    \"servers\": {\r\n        \"only\": {\r\n            \"a\": 1\r\n        }\r\n    }\r\n}"
+        );
+    }
+}
@@ -1 +1,5 @@
+pub mod fs;
+pub mod jsonc;
 pub mod os;
+pub mod process;
+pub mod secrets;
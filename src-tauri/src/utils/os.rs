@@ -11,9 +11,78 @@ pub fn get_home() -> Result<PathBuf> {
     current_home
 }
 
-pub fn detect_shell() -> Result<String> {
+/// Base directory for per-user configuration files, honouring `XDG_CONFIG_HOME`
+/// on Linux.
+pub fn get_config_dir() -> Result<PathBuf> {
+    #[cfg(target_os = "macos")]
+    {
+        Ok(get_home()?.join("Library/Application Support"))
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let appdata = std::env::var("APPDATA").context("Failed to get APPDATA environment variable")?;
+        Ok(PathBuf::from(appdata))
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        xdg_dir("XDG_CONFIG_HOME", ".config")
+    }
+}
+
+/// Base directory for per-user application data, honouring `XDG_DATA_HOME`
+/// on Linux.
+pub fn get_data_dir() -> Result<PathBuf> {
     #[cfg(target_os = "macos")]
     {
+        Ok(get_home()?.join("Library/Application Support"))
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        Ok(get_home()?.join("AppData").join("Local"))
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        xdg_dir("XDG_DATA_HOME", ".local/share")
+    }
+}
+
+/// Directories under home that the XDG base directory variables stand for when unset.
+const XDG_FALLBACKS: [(&str, &str); 4] = [
+    ("XDG_CONFIG_HOME", ".config"),
+    ("XDG_DATA_HOME", ".local/share"),
+    ("XDG_STATE_HOME", ".local/state"),
+    ("XDG_CACHE_HOME", ".cache"),
+];
+
+/// The directory an XDG base directory variable such as `XDG_STATE_HOME` names, or
+/// `None` when `var` is not one of them.
+pub fn xdg_base_dir(var: &str) -> Option<Result<PathBuf>> {
+    let (_, fallback) = XDG_FALLBACKS.iter().find(|(name, _)| *name == var)?;
+    Some(xdg_dir(var, fallback))
+}
+
+fn xdg_dir(var: &str, fallback: &str) -> Result<PathBuf> {
+    // The XDG spec says relative paths in these variables are invalid and must be ignored.
+    match std::env::var_os(var).map(PathBuf::from) {
+        Some(dir) if dir.is_absolute() => {
+            trace!("{} directory: {}", var, dir.to_string_lossy());
+            Ok(dir)
+        }
+        _ => Ok(get_home()?.join(fallback)),
+    }
+}
+
+pub fn detect_shell() -> Result<String> {
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    {
+        // Desktop launchers on Linux don't always export SHELL, so fall back to plain sh there.
+        #[cfg(target_os = "linux")]
+        let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string());
+        #[cfg(target_os = "macos")]
         let shell = std::env::var("SHELL").context("Failed to get SHELL environment variable")?;
         let shell_name = std::path::Path::new(&shell)
             .file_name()
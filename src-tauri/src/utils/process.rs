@@ -0,0 +1,187 @@
+use anyhow::{anyhow, bail, Context, Result};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use sysinfo::{
+    Pid, Process, ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, System, UpdateKind,
+};
+
+/// How long `quit_process` waits for a program to exit after asking it to.
+const QUIT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// A running program with what is needed to start it again.
+#[derive(Debug, Clone)]
+pub struct RunningProcess {
+    pub pid: u32,
+    pub exe: Option<PathBuf>,
+    /// Arguments it was started with, without the program itself.
+    pub args: Vec<OsString>,
+}
+
+fn load_processes(processes: ProcessesToUpdate) -> System {
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        processes,
+        true,
+        ProcessRefreshKind::new()
+            .with_exe(UpdateKind::OnlyIfNotSet)
+            .with_cmd(UpdateKind::OnlyIfNotSet),
+    );
+    system
+}
+
+/// Programs whose executable is named one of `names`. Only main processes are
+/// returned; the helper processes an Electron app starts under the same name are not.
+pub fn find_processes(names: &[&str]) -> Vec<RunningProcess> {
+    if names.is_empty() {
+        return vec![];
+    }
+    let system = load_processes(ProcessesToUpdate::All);
+    let matches = |process: &Process| {
+        process.thread_kind().is_none()
+            && process.status() != ProcessStatus::Zombie
+            && names.iter().any(|name| process.name() == *name)
+    };
+    system
+        .processes()
+        .values()
+        .filter(|process| matches(process))
+        .filter(|process| {
+            !process
+                .parent()
+                .and_then(|parent| system.process(parent))
+                .is_some_and(matches)
+        })
+        .map(|process| RunningProcess {
+            pid: process.pid().as_u32(),
+            exe: process.exe().map(Path::to_path_buf),
+            args: process.cmd().iter().skip(1).cloned().collect(),
+        })
+        .collect()
+}
+
+fn is_running(pid: u32) -> bool {
+    let pid = Pid::from_u32(pid);
+    load_processes(ProcessesToUpdate::Some(&[pid]))
+        .process(pid)
+        .is_some_and(|process| process.status() != ProcessStatus::Zombie)
+}
+
+/// Asks the program to quit, as closing its last window would, and waits until it has.
+pub fn quit_process(process: &RunningProcess) -> Result<()> {
+    #[cfg(unix)]
+    {
+        let pid = Pid::from_u32(process.pid);
+        let asked = load_processes(ProcessesToUpdate::Some(&[pid]))
+            .process(pid)
+            .and_then(|running| running.kill_with(sysinfo::Signal::Term));
+        if asked == Some(false) {
+            bail!("Failed to ask process {} to quit", process.pid);
+        }
+    }
+    #[cfg(windows)]
+    {
+        // Without /F, taskkill sends the close message a click on the window's X does.
+        let status = Command::new("taskkill")
+            .args(["/PID", &process.pid.to_string()])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()?;
+        if !status.success() {
+            bail!("Failed to ask process {} to quit", process.pid);
+        }
+    }
+
+    let started = Instant::now();
+    while is_running(process.pid) {
+        if started.elapsed() > QUIT_TIMEOUT {
+            bail!(
+                "Process {} did not quit within {} seconds",
+                process.pid,
+                QUIT_TIMEOUT.as_secs()
+            );
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    Ok(())
+}
+
+/// Quits all of `processes`, then starts them again. Blocks until they have quit.
+pub fn restart_processes(processes: &[RunningProcess]) -> Result<()> {
+    for process in processes {
+        quit_process(process)?;
+    }
+    for process in processes {
+        relaunch_process(process)?;
+    }
+    Ok(())
+}
+
+/// Starts the program again with its original arguments, detached from MCPHub.
+pub fn relaunch_process(process: &RunningProcess) -> Result<()> {
+    let exe = process
+        .exe
+        .as_ref()
+        .ok_or_else(|| anyhow!("Executable of process {} is unknown", process.pid))?;
+
+    // Apps started through Launch Services get their usual environment and Dock icon.
+    #[cfg(target_os = "macos")]
+    if let Some(bundle) = exe
+        .ancestors()
+        .find(|dir| dir.extension().is_some_and(|e| e == "app"))
+    {
+        Command::new("open")
+            .arg("-a")
+            .arg(bundle)
+            .status()
+            .with_context(|| format!("Failed to start {}", bundle.to_string_lossy()))?;
+        return Ok(());
+    }
+
+    Command::new(exe)
+        .args(&process.args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to start {}", exe.to_string_lossy()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn restarted_processes_run_again_with_the_same_arguments() {
+        // An argument no other `sleep` on the machine is likely to have.
+        let args = [OsString::from("1789")];
+        let find = || {
+            find_processes(&["sleep"])
+                .into_iter()
+                .filter(|process| process.args == args)
+                .collect::<Vec<_>>()
+        };
+        let mut child = Command::new("sleep").args(&args).spawn().unwrap();
+        let before = find();
+        assert_eq!(
+            before.iter().map(|process| process.pid).collect::<Vec<_>>(),
+            [child.id()]
+        );
+
+        let restarted = restart_processes(&before);
+        child.wait().unwrap();
+        let after = find();
+        for process in &after {
+            let pid = Pid::from_u32(process.pid);
+            if let Some(running) = load_processes(ProcessesToUpdate::Some(&[pid])).process(pid) {
+                running.kill();
+            }
+        }
+        restarted.unwrap();
+        assert_eq!(after.len(), 1);
+        assert_ne!(after[0].pid, child.id());
+    }
+}
@@ -0,0 +1,104 @@
+use crate::utils::fs::path_key;
+#[cfg(not(test))]
+use anyhow::Context;
+use anyhow::Result;
+#[cfg(test)]
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Parts of an env variable name that mark its value as a credential.
+const SECRET_MARKERS: [&str; 9] = [
+    "KEY",
+    "TOKEN",
+    "SECRET",
+    "PASSWORD",
+    "PASSWD",
+    "CREDENTIAL",
+    "AUTH",
+    "COOKIE",
+    "SESSION",
+];
+
+/// Whether the env variable `name` likely holds a secret, judging by its name alone.
+pub fn is_secret_key(name: &str) -> bool {
+    let name = name.to_uppercase();
+    SECRET_MARKERS.iter().any(|marker| name.contains(marker))
+}
+
+/// Shown in place of secret values.
+pub const SECRET_MASK: &str = "********";
+
+/// Replaces the values of the variables in `env` that `is_secret` picks with
+/// [`SECRET_MASK`].
+pub fn mask_env(env: &mut HashMap<String, String>, is_secret: impl Fn(&str) -> bool) {
+    for (key, value) in env.iter_mut() {
+        if is_secret(key) {
+            *value = SECRET_MASK.to_string();
+        }
+    }
+}
+
+/// Keyring service that MCPHub keeps env secrets under.
+#[cfg(not(test))]
+const KEYRING_SERVICE: &str = "MCPHub";
+
+/// Secret store account for env variable `key` of server `instance_id` in the client
+/// config at `config_path`.
+pub fn secret_account(config_path: &Path, instance_id: &str, key: &str) -> String {
+    format!("{}/{}/{}", path_key(config_path), instance_id, key)
+}
+
+#[cfg(not(test))]
+pub fn get_secret(account: &str) -> Result<Option<String>> {
+    match keyring::Entry::new(KEYRING_SERVICE, account)?.get_password() {
+        Ok(value) => Ok(Some(value)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("Failed to read secret {}", account)),
+    }
+}
+
+#[cfg(not(test))]
+pub fn set_secret(account: &str, value: &str) -> Result<()> {
+    keyring::Entry::new(KEYRING_SERVICE, account)?
+        .set_password(value)
+        .with_context(|| format!("Failed to store secret {}", account))
+}
+
+/// Removes the secret; removing one that doesn't exist is not an error.
+#[cfg(not(test))]
+pub fn delete_secret(account: &str) -> Result<()> {
+    match keyring::Entry::new(KEYRING_SERVICE, account)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e).with_context(|| format!("Failed to delete secret {}", account)),
+    }
+}
+
+/// Stands in for the OS keyring in tests, which can't rely on a secret service.
+#[cfg(test)]
+static MOCK_STORE: std::sync::Mutex<BTreeMap<String, String>> =
+    std::sync::Mutex::new(BTreeMap::new());
+
+#[cfg(test)]
+fn mock_store() -> std::sync::MutexGuard<'static, BTreeMap<String, String>> {
+    MOCK_STORE
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+#[cfg(test)]
+pub fn get_secret(account: &str) -> Result<Option<String>> {
+    Ok(mock_store().get(account).cloned())
+}
+
+#[cfg(test)]
+pub fn set_secret(account: &str, value: &str) -> Result<()> {
+    mock_store().insert(account.to_string(), value.to_string());
+    Ok(())
+}
+
+#[cfg(test)]
+pub fn delete_secret(account: &str) -> Result<()> {
+    mock_store().remove(account);
+    Ok(())
+}
@@ -2,7 +2,7 @@ import { Dialog, DialogContent, DialogFooter, DialogHeader, DialogTitle } from "
 import { Input } from "@/components/ui/input";
 import { open as pathSelect } from '@tauri-apps/plugin-dialog';
 import { Label } from "@/components/ui/label";
-import type { InputArg } from "@/types/server";
+import type { EnvVarSchema, InputArg } from "@/types/server";
 import { open as urlOpen } from '@tauri-apps/plugin-shell';
 import { useState } from 'react';
 import ReactMarkdown from 'react-markdown';
@@ -13,31 +13,34 @@ interface ConfigModalProps {
     isOpen: boolean
     onClose: () => void
     env: Record<string, string>
+    envSchema: Record<string, EnvVarSchema>
     guide: string
-    inputArg: InputArg
-    onSave: (config: Record<string, string>, args: string[]) => void
+    inputArgs: InputArg[]
+    onSave: (config: Record<string, string>, args: Record<string, string[]>) => void
 }
 
-export function ConfigModal({ isOpen, onClose, env, guide, inputArg, onSave }: ConfigModalProps) {
-    const [config, setConfig] = useState<Record<string, string>>({})
+export function ConfigModal({ isOpen, onClose, env, envSchema, guide, inputArgs, onSave }: ConfigModalProps) {
+    const [config, setConfig] = useState<Record<string, string>>({ ...env })
 
     const handleInputChange = (key: string, value: string) => {
         setConfig(prev => ({ ...prev, [key]: value }))
     }
-    const [argValues, setArgValues] = useState<string[]>(inputArg.value);
+    const [argValues, setArgValues] = useState<Record<string, string[]>>(
+        Object.fromEntries(inputArgs.map((inputArg) => [inputArg.name, inputArg.value]))
+    );
 
-    const handleArgValueChange = (index: number, value: string) => {
-        const newValues = [...argValues];
+    const handleArgValueChange = (name: string, index: number, value: string) => {
+        const newValues = [...(argValues[name] || [])];
         newValues[index] = value;
-        setArgValues(newValues);
+        setArgValues(prev => ({ ...prev, [name]: newValues }));
     };
 
-    const handleAddValue = () => {
-        setArgValues([...argValues, ""]);
+    const handleAddValue = (name: string) => {
+        setArgValues(prev => ({ ...prev, [name]: [...(prev[name] || []), ""] }));
     };
 
     
-    const handleFileSelect = async (index: number) => {
+    const handleFileSelect = async (inputArg: InputArg, index: number) => {
         let selected: string | null;
         if (inputArg.class === "DirectoryPath") {
             selected = await pathSelect({
@@ -51,7 +54,7 @@ export function ConfigModal({ isOpen, onClose, env, guide, inputArg, onSave }: C
         }
         
         if (selected) {
-            handleArgValueChange(index, selected as string);
+            handleArgValueChange(inputArg.name, index, selected as string);
         }
     };
 
@@ -59,8 +62,6 @@ export function ConfigModal({ isOpen, onClose, env, guide, inputArg, onSave }: C
         onSave(config, argValues)
         onClose()
     }
-    console.log(argValues)
-    console.log(inputArg)
 
     return (
         <Dialog open={isOpen} onOpenChange={onClose}>
@@ -94,33 +95,60 @@ export function ConfigModal({ isOpen, onClose, env, guide, inputArg, onSave }: C
                 </DialogHeader>
                 <div className="px-6 py-4 border-y">
                     <div className="space-y-4">
-                        {Object.keys(env).map((key) => (
-                            <div key={key} className="flex flex-col space-y-2">
-                                <Label htmlFor={key} className="font-medium">
-                                    {key}
-                                </Label>
-                                <Input
-                                    id={key}
-                                    placeholder={`Enter your ${key.toLowerCase()}`}
-                                    value={config[key] || env[key] || ''}
-                                    onChange={(e) => handleInputChange(key, e.target.value)}
-                                />
-                            </div>
-                        ))}
-                        {
-                            inputArg.name && (
-                                <div className="space-y-4">
+                        {Object.keys(env).map((key) => {
+                            const schema = envSchema[key];
+                            return (
+                                <div key={key} className="flex flex-col space-y-2">
+                                    <Label htmlFor={key} className="font-medium">
+                                        {key}
+                                        {schema?.required && <span className="text-red-500 ml-1">*</span>}
+                                    </Label>
+                                    <Input
+                                        id={key}
+                                        type={schema?.secret ? "password" : "text"}
+                                        required={schema?.required}
+                                        placeholder={`Enter your ${key.toLowerCase()}`}
+                                        value={config[key] ?? ''}
+                                        onChange={(e) => handleInputChange(key, e.target.value)}
+                                    />
+                                    {schema?.description && (
+                                        <p className="text-sm text-muted-foreground">
+                                            {schema.description}
+                                        </p>
+                                    )}
+                                    {schema?.helpUrl && (
+                                        <a
+                                            onClick={() => urlOpen(schema.helpUrl!)}
+                                            className="text-sm text-blue-500 hover:underline cursor-pointer"
+                                        >
+                                            Where to get it
+                                        </a>
+                                    )}
+                                </div>
+                            )
+                        })}
+                        {inputArgs.map((inputArg) => {
+                            const values = argValues[inputArg.name] || [];
+                            return (
+                                <div key={inputArg.name} className="space-y-4">
                                     <div className="flex flex-col space-y-2">
                                         <Label className="text-lg font-semibold">
                                             {inputArg.name}
                                         </Label>
-                                        
-                                        {(argValues.length > 0 ? argValues : [""]).map((value, index) => (
+
+                                        {inputArg.class === "Switch" ? (
+                                            <input
+                                                type="checkbox"
+                                                className="h-4 w-4"
+                                                checked={values[0] === "true"}
+                                                onChange={(e) => handleArgValueChange(inputArg.name, 0, e.target.checked ? "true" : "false")}
+                                            />
+                                        ) : (values.length > 0 ? values : [""]).map((value, index) => (
                                             <div key={index} className="flex gap-2">
                                                 {inputArg.class === "Text" ? (
                                                     <Input
                                                         value={value}
-                                                        onChange={(e) => handleArgValueChange(index, e.target.value)}
+                                                        onChange={(e) => handleArgValueChange(inputArg.name, index, e.target.value)}
                                                         placeholder={`Enter ${inputArg.name.toLowerCase()}`}
                                                     />
                                                 ) : (
@@ -133,7 +161,7 @@ export function ConfigModal({ isOpen, onClose, env, guide, inputArg, onSave }: C
                                                         <Button
                                                             type="button"
                                                             variant="outline"
-                                                            onClick={() => handleFileSelect(index)}
+                                                            onClick={() => handleFileSelect(inputArg, index)}
                                                         >
                                                             <FolderOpen className="h-4 w-4" />
                                                         </Button>
@@ -146,7 +174,7 @@ export function ConfigModal({ isOpen, onClose, env, guide, inputArg, onSave }: C
                                             <Button
                                                 type="button"
                                                 variant="outline"
-                                                onClick={handleAddValue}
+                                                onClick={() => handleAddValue(inputArg.name)}
                                                 className="w-full mt-2"
                                             >
                                                 <Plus className="h-4 w-4 mr-2" />
@@ -161,7 +189,8 @@ export function ConfigModal({ isOpen, onClose, env, guide, inputArg, onSave }: C
                                         </p>
                                     )}
                                 </div>
-                            )}
+                            )
+                        })}
                     </div>
                 </div>
                 <DialogFooter className="p-6 pt-4">
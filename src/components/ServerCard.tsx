@@ -2,7 +2,7 @@ import { Tag } from "@/components/Tag"
 import { Avatar, AvatarFallback, AvatarImage } from "@/components/ui/avatar"
 import { Button } from "@/components/ui/button"
 import { Card, CardContent } from "@/components/ui/card"
-import type { InstallStatus, ServerCardData } from '@/types/server'
+import type { AppError, InstallStatus, ServerCardData } from '@/types/server'
 import { getRelativeTime } from '@/utils/getRelativeTime'
 import { invoke } from "@tauri-apps/api/core"
 import { motion } from 'framer-motion'
@@ -23,19 +23,27 @@ export function ServerCard({
     tags,
     isInstalled,
     env,
+    envSchema,
     guide,
-    inputArg,
+    inputArgs,
 }: ServerCardProps) {
     const [isHovered, setIsHovered] = useState(false)
     const [isConfigModalOpen, setIsConfigModalOpen] = useState(false)
     const [installStatus, setInstallStatus] = useState<InstallStatus>(isInstalled ? 'installed' : 'install')
+    const [error, setError] = useState<string | null>(null)
     const relativeTime = getRelativeTime(publishDate)
 
-    const handleConfigSave = async (config: Record<string, string>, args: string[]) => {
-        console.log('Saved config:', config)
+    const handleConfigSave = async (config: Record<string, string>, args: Record<string, string[]>) => {
+        const previousStatus = installStatus
+        setError(null)
         setInstallStatus('installing')
-        await invoke('update_server', { serverId: id, env: config , inputArg: args})
-        setInstallStatus('installed')
+        try {
+            await invoke('update_server', { serverId: id, env: config, inputArgs: args })
+            setInstallStatus('installed')
+        } catch (e) {
+            setError((e as AppError).message ?? String(e))
+            setInstallStatus(previousStatus)
+        }
     }
 
     const handleInstall = async () => {
@@ -45,7 +53,7 @@ export function ServerCard({
             return;
         }
 
-        if (Object.keys(env).length === 0 && !inputArg.name) {
+        if (Object.keys(env).length === 0 && inputArgs.length === 0) {
             setInstallStatus('installing');
             await invoke('install_server', { serverId: id });
             setInstallStatus('installed');
@@ -105,7 +113,7 @@ export function ServerCard({
                             <h3 className="font-semibold text-base leading-none mb-1">{title}</h3>
                             <p className="text-sm text-muted-foreground">{creator}</p>
                         </div>
-                        {installStatus === 'installed' && (Object.keys(env).length > 0 || inputArg.name) && (
+                        {installStatus === 'installed' && (Object.keys(env).length > 0 || inputArgs.length > 0) && (
                             <Button
                                 variant="outline"
                                 size="icon"
@@ -142,6 +150,7 @@ export function ServerCard({
                             {relativeTime}
                         </motion.p>
                     </div>
+                    {error && <p className="text-sm text-destructive mb-3">{error}</p>}
                     <Button
                         className="w-full"
                         variant={installStatus === 'installed' ? 'secondary' : 'default'}
@@ -156,8 +165,9 @@ export function ServerCard({
                 isOpen={isConfigModalOpen}
                 onClose={() => setIsConfigModalOpen(false)}
                 env={env}
+                envSchema={envSchema}
                 guide={guide}
-                inputArg={inputArg}
+                inputArgs={inputArgs}
                 onSave={handleConfigSave}
             />
         </motion.div>
@@ -1,11 +1,28 @@
 export interface InputArg {
   name: string
   description: string
-  class: 'Text' | 'Select' | 'FilePath' | 'DirectoryPath'
+  flag?: string
+  class: 'Text' | 'Select' | 'FilePath' | 'DirectoryPath' | 'Switch'
   multiplicity: 'Single' | 'Multiple'
   value: string[]
 }
 
+export interface EnvVarSchema {
+  required: boolean
+  secret: boolean
+  description: string
+  pattern?: string | null
+  default?: string | null
+  helpUrl?: string | null
+}
+
+/** What a failed command rejects with. */
+export interface AppError {
+  code: string
+  message: string
+  context: unknown
+}
+
 interface BaseServerCardData {
   id: string
   title: string
@@ -16,8 +33,9 @@ interface BaseServerCardData {
   tags: string[]
   isInstalled: boolean,
   env: Record<string, string>
+  envSchema: Record<string, EnvVarSchema>
   guide: string
-  inputArg: InputArg
+  inputArgs: InputArg[]
 }
 
 export interface ServerCardData extends BaseServerCardData {